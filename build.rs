@@ -16,8 +16,7 @@ fn main() {
         let numeric_version = version.replace(".", ",") + ",0"; // e.g., "1.0.0" -> "1,0,0,0"
 
         // Read the app.rc file
-        let app_rc_content = fs::read_to_string("res/app.rc")
-            .expect("Failed to read res/app.rc");
+        let app_rc_content = fs::read_to_string("res/app.rc").expect("Failed to read res/app.rc");
 
         // Create the content for tmp.rc with version replacements
         let tmp_rc_content = app_rc_content
@@ -25,10 +24,9 @@ fn main() {
             .replace("{{NUMERIC_VERSION}}", &numeric_version);
 
         // Write to tmp.rc
-        fs::write("res/tmp.rc", tmp_rc_content)
-            .expect("Failed to write res/tmp.rc");
+        fs::write("res/tmp.rc", tmp_rc_content).expect("Failed to write res/tmp.rc");
 
         // Compile the tmp.rc file
         let _ = embed_resource::compile("res/tmp.rc", embed_resource::NONE);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,569 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use super::LockBackend;
+use super::lock_probe;
+use super::mounts::{mount_of, read_mounts};
+use super::proc_locks::{ProcLock, holder_of, parse_fd_locks, read_proc_locks};
+use crate::error::FlcError;
+use crate::types::{
+    DeletedFile, DeletedFileHolder, DeletedFiles, FileLockInfo, HandleKind, LockClass,
+    LockConflict, LockRecord, LockRequest, LockWait, OpenHandle, ProcessInfo,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+// backend ที่อ่านข้อมูลจาก /proc ของ Linux
+pub struct ProcBackend;
+
+// (device, inode) ที่ใช้ระบุไฟล์ แทนการเทียบ path
+type FileId = (u64, u64);
+
+impl LockBackend for ProcBackend {
+    fn check_file_locks(&self, file_path: &str) -> Result<FileLockInfo, FlcError> {
+        self.check_many_file_locks(&[file_path])?.remove(0)
+    }
+
+    fn check_many_file_locks(
+        &self,
+        file_paths: &[&str],
+    ) -> Result<Vec<Result<FileLockInfo, FlcError>>, FlcError> {
+        // stat ตาม symlink ไปยังไฟล์จริง ไฟล์ที่ stat ไม่ได้จะได้ error ของตัวเอง โดยไม่กระทบไฟล์อื่น
+        let files = file_paths
+            .iter()
+            .map(|file_path| {
+                let id = fs::metadata(file_path)
+                    .map(|metadata| (metadata.dev(), metadata.ino()))
+                    .map_err(|e| FlcError::io(format!("Failed to stat '{}'", file_path), e));
+                (file_path.to_string(), id)
+            })
+            .collect();
+        self.scan(files)
+    }
+
+    // ไม่มี path ให้ stat จึงใช้ (device, inode) ที่ระบุมาโดยตรง
+    fn check_inode_locks(&self, ids: &[(u64, u64)]) -> Result<Vec<FileLockInfo>, FlcError> {
+        let files = ids
+            .iter()
+            .map(|&(dev, ino)| (format!("{}:{}", dev, ino), Ok((dev, ino))))
+            .collect();
+        self.scan(files)?.into_iter().collect()
+    }
+
+    // หา fd ที่ชี้ไปยังไฟล์ "(deleted)" ของทุก process แล้วรวมตามไฟล์ เพราะหลาย process อาจเปิดไฟล์เดียวกัน
+    fn deleted_files(&self) -> Result<DeletedFiles, FlcError> {
+        let proc_dir = fs::read_dir("/proc").map_err(|e| FlcError::BackendUnavailable {
+            reason: format!("Failed to read /proc: {}", e),
+            code: e.raw_os_error(),
+        })?;
+        // ถ้าอ่าน mountinfo ไม่ได้ก็ยังแสดงไฟล์ได้ เพียงแต่ไม่รู้ filesystem
+        let mounts = read_mounts().unwrap_or_default();
+
+        let mut files: Vec<DeletedFile> = Vec::new();
+        let mut by_id: HashMap<FileId, usize> = HashMap::new();
+        let mut inaccessible = Vec::new();
+
+        for entry in proc_dir.flatten() {
+            let Some(pid) = entry
+                .file_name()
+                .to_str()
+                .and_then(|s| s.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            let fds = match fs::read_dir(entry.path().join("fd")) {
+                Ok(fds) => fds,
+                Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                    inaccessible.push(pid);
+                    continue;
+                }
+                Err(_) => continue,
+            };
+
+            let mut name = None;
+            for fd in fds.flatten() {
+                let Ok(target) = fs::read_link(fd.path()) else {
+                    continue;
+                };
+                let target = target.to_string_lossy();
+                let Some(path) = target.strip_suffix(" (deleted)") else {
+                    continue;
+                };
+                // memfd เป็นหน่วยความจำ ไม่ได้ใช้พื้นที่ดิสก์
+                if path.starts_with("/memfd:") {
+                    continue;
+                }
+                // ชื่อไฟล์จริงอาจลงท้ายด้วย " (deleted)" และไฟล์ที่ยังมี hardlink อื่นไม่ได้ถูกลบจริง
+                let Ok(metadata) = fs::metadata(fd.path()) else {
+                    continue;
+                };
+                if !metadata.is_file() || metadata.nlink() != 0 {
+                    continue;
+                }
+                let Some(number) = fd.file_name().to_str().and_then(|s| s.parse().ok()) else {
+                    continue;
+                };
+
+                let id = (metadata.dev(), metadata.ino());
+                let index = *by_id.entry(id).or_insert_with(|| {
+                    files.push(DeletedFile {
+                        path: path.to_string(),
+                        dev: id.0,
+                        ino: id.1,
+                        size: metadata.len(),
+                        allocated: metadata.blocks() * 512,
+                        filesystem: mount_of(&mounts, id.0, path)
+                            .map(|mount| mount.filesystem.clone()),
+                        holders: Vec::new(),
+                    });
+                    files.len() - 1
+                });
+                let holders = &mut files[index].holders;
+                match holders.iter_mut().find(|holder| holder.pid == pid) {
+                    Some(holder) => holder.fds.push(number),
+                    None => holders.push(DeletedFileHolder {
+                        pid,
+                        name: name.get_or_insert_with(|| process_name(pid)).clone(),
+                        fds: vec![number],
+                    }),
+                }
+            }
+        }
+
+        for file in &mut files {
+            for holder in &mut file.holders {
+                holder.fds.sort_unstable();
+            }
+        }
+        files.sort_by(|a, b| {
+            b.allocated
+                .cmp(&a.allocated)
+                .then(b.size.cmp(&a.size))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+
+        Ok(DeletedFiles {
+            files,
+            inaccessible,
+        })
+    }
+
+    fn kill_process(&self, pid: u32) -> Result<(), FlcError> {
+        // ส่ง SIGKILL ให้เทียบเท่ากับ TerminateProcess บน Windows
+        let result = unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) };
+
+        if result != 0 {
+            let error = io::Error::last_os_error();
+            Err(match error.raw_os_error() {
+                Some(libc::ESRCH) => FlcError::ProcessGone { pid },
+                Some(libc::EPERM) => FlcError::PermissionDenied {
+                    action: format!("terminating process {}", pid),
+                    code: Some(libc::EPERM),
+                },
+                _ => FlcError::io(format!("Failed to terminate process {}", pid), error),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn get_process_path(&self, pid: u32) -> String {
+        match fs::read_link(Path::new("/proc").join(pid.to_string()).join("exe")) {
+            Ok(path) => path.to_string_lossy().into_owned(),
+            Err(_) => format!("Unknown (PID: {})", pid),
+        }
+    }
+
+    fn get_process_user(&self, pid: u32) -> Option<String> {
+        // เจ้าของ /proc/<pid> คือ effective uid ของ process
+        let uid = fs::metadata(Path::new("/proc").join(pid.to_string()))
+            .ok()?
+            .uid();
+        Some(user_name(uid).unwrap_or_else(|| uid.to_string()))
+    }
+
+    fn lock_waits(&self) -> Result<Vec<LockWait>, FlcError> {
+        let locks = read_proc_locks().map_err(|e| FlcError::io("Failed to read /proc/locks", e))?;
+
+        // หา path จาก fd ของ holder ก่อน ถ้าไม่เจอค่อยดูจาก waiter
+        Ok(collect_waits(&locks, None, |wait| {
+            let targets = HashSet::from([(wait.dev, wait.ino)]);
+            [wait.holder_pid, wait.waiter_pid]
+                .iter()
+                .find_map(|pid| {
+                    process_handles(&Path::new("/proc").join(pid.to_string()), &targets)
+                        .ok()?
+                        .into_iter()
+                        .next()
+                })
+                .map(|(_, handle)| handle.path)
+                .unwrap_or_else(|| {
+                    format!(
+                        "device {}:{} inode {}",
+                        libc::major(wait.dev),
+                        libc::minor(wait.dev),
+                        wait.ino
+                    )
+                })
+        }))
+    }
+
+    fn probe_lock(
+        &self,
+        file_path: &str,
+        request: &LockRequest,
+    ) -> Result<Option<LockConflict>, FlcError> {
+        let conflict = lock_probe::probe(file_path, request)?;
+        Ok(conflict.map(|mut conflict| {
+            conflict.name = conflict.pid.map(process_name);
+            conflict
+        }))
+    }
+}
+
+impl ProcBackend {
+    // เดิน /proc ครั้งเดียวสำหรับทุกไฟล์ โดยเทียบ fd ของแต่ละ process กับชุดของ (device, inode)
+    // files คือชื่อที่ใช้แสดงผลคู่กับ (device, inode) หรือ error ของไฟล์นั้น
+    // หมายเหตุ: process ของ user อื่นจะมองไม่เห็น fd หากไม่ได้รันด้วยสิทธิ์ root
+    fn scan(
+        &self,
+        files: Vec<(String, Result<FileId, FlcError>)>,
+    ) -> Result<Vec<Result<FileLockInfo, FlcError>>, FlcError> {
+        let targets: HashSet<FileId> = files
+            .iter()
+            .filter_map(|(_, id)| id.as_ref().ok())
+            .copied()
+            .collect();
+
+        let proc_dir = fs::read_dir("/proc").map_err(|e| FlcError::BackendUnavailable {
+            reason: format!("Failed to read /proc: {}", e),
+            code: e.raw_os_error(),
+        })?;
+
+        // อ่าน /proc/locks ครั้งเดียว ถ้าอ่านไม่ได้ก็แสดงแค่ process ที่เปิดไฟล์
+        let all_locks = read_proc_locks().unwrap_or_default();
+
+        let mut holders: HashMap<FileId, Vec<ProcessInfo>> = HashMap::new();
+        let mut inaccessible = Vec::new();
+
+        for entry in proc_dir.flatten() {
+            if targets.is_empty() {
+                break;
+            }
+            // สนใจเฉพาะ directory ที่เป็นตัวเลข (PID)
+            let Some(pid) = entry
+                .file_name()
+                .to_str()
+                .and_then(|s| s.parse::<u32>().ok())
+            else {
+                continue;
+            };
+
+            let handles = match process_handles(&entry.path(), &targets) {
+                Ok(handles) => handles,
+                Err(_) => {
+                    inaccessible.push(pid);
+                    continue;
+                }
+            };
+            if handles.is_empty() {
+                continue;
+            }
+
+            // ข้อมูลของ process อ่านครั้งเดียว แม้จะเปิดหลายไฟล์
+            let name = process_name(pid);
+            let path = self.get_process_path(pid);
+            let user = self.get_process_user(pid);
+            let mut by_file: Vec<(FileId, Vec<OpenHandle>)> = Vec::new();
+            for (id, handle) in handles {
+                match by_file.iter_mut().find(|(file, _)| *file == id) {
+                    Some((_, file_handles)) => file_handles.push(handle),
+                    None => by_file.push((id, vec![handle])),
+                }
+            }
+            for (id, handles) in by_file {
+                holders.entry(id).or_default().push(ProcessInfo {
+                    pid,
+                    name: name.clone(),
+                    path: path.clone(),
+                    user: user.clone(),
+                    locks: locks_held_by(&all_locks, id, pid, &handles),
+                    handles,
+                });
+            }
+        }
+
+        Ok(files
+            .into_iter()
+            .map(|(file_path, id)| {
+                let id = id?;
+                Ok(FileLockInfo {
+                    processes: holders.get(&id).cloned().unwrap_or_default(),
+                    waiters: collect_waits(&all_locks, Some(id), |_| file_path.clone()),
+                    file_path,
+                    inaccessible: inaccessible.clone(),
+                })
+            })
+            .collect())
+    }
+}
+
+// แปลง uid เป็นชื่อ user จากฐานข้อมูล passwd
+fn user_name(uid: u32) -> Option<String> {
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let mut buffer = vec![0 as libc::c_char; 4096];
+
+    let rc = unsafe {
+        libc::getpwuid_r(
+            uid,
+            &mut passwd,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    };
+    if rc != 0 || result.is_null() {
+        return None;
+    }
+
+    let name = unsafe { std::ffi::CStr::from_ptr(passwd.pw_name) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+// หาทุกช่องทางที่ process อ้างถึงไฟล์ใน targets: fd, cwd, root, exe และ mmap
+// process ที่จบไปแล้วจะได้รายการว่าง ส่วน process ที่ไม่มีสิทธิ์อ่าน fd จะได้ error
+fn process_handles(
+    proc_path: &Path,
+    targets: &HashSet<FileId>,
+) -> io::Result<Vec<(FileId, OpenHandle)>> {
+    let mut handles = Vec::new();
+
+    // stat ผ่าน /proc/<pid>/fd/<n> จะได้ข้อมูลของไฟล์ที่เปิดอยู่จริง
+    // แม้ไฟล์จะถูกเปิดผ่าน symlink หรือ hardlink อื่น
+    let fds = match fs::read_dir(proc_path.join("fd")) {
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => return Err(e),
+        fds => fds,
+    };
+    if let Ok(fds) = fds {
+        for fd in fds.flatten() {
+            let Some(id) = fs::metadata(fd.path())
+                .ok()
+                .map(|m| (m.dev(), m.ino()))
+                .filter(|id| targets.contains(id))
+            else {
+                continue;
+            };
+            let Ok(path) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let number = fd.file_name().to_str().and_then(|s| s.parse().ok());
+            let info = number
+                .and_then(|number: u32| {
+                    fs::read_to_string(proc_path.join("fdinfo").join(number.to_string())).ok()
+                })
+                .unwrap_or_default();
+            let (read, write) = fd_access(&info).unwrap_or((false, false));
+            let locks = parse_fd_locks(&info)
+                .into_iter()
+                .filter(|lock| (lock.dev, lock.ino) == id)
+                .map(|lock| lock.record)
+                .collect();
+            handles.push((
+                id,
+                OpenHandle {
+                    kind: HandleKind::Fd,
+                    fd: number,
+                    path: path.to_string_lossy().into_owned(),
+                    read,
+                    write,
+                    locks,
+                },
+            ));
+        }
+        handles.sort_by_key(|(_, handle)| handle.fd);
+    }
+
+    for (link, kind) in [
+        ("cwd", HandleKind::Cwd),
+        ("root", HandleKind::Root),
+        ("exe", HandleKind::Exe),
+    ] {
+        let link = proc_path.join(link);
+        if let Ok(metadata) = fs::metadata(&link)
+            && targets.contains(&(metadata.dev(), metadata.ino()))
+            && let Ok(path) = fs::read_link(&link)
+        {
+            handles.push((
+                (metadata.dev(), metadata.ino()),
+                OpenHandle {
+                    kind,
+                    fd: None,
+                    path: path.to_string_lossy().into_owned(),
+                    read: kind == HandleKind::Exe,
+                    write: false,
+                    locks: Vec::new(),
+                },
+            ));
+        }
+    }
+
+    handles.extend(mapped_handles(proc_path, targets));
+
+    Ok(handles)
+}
+
+// อ่าน access mode ของ fd จากเนื้อหาของ /proc/<pid>/fdinfo/<fd> ("flags:" เป็นเลขฐาน 8)
+fn fd_access(info: &str) -> Option<(bool, bool)> {
+    let flags = info
+        .lines()
+        .find_map(|line| line.strip_prefix("flags:"))
+        .and_then(|flags| i32::from_str_radix(flags.trim(), 8).ok())?;
+    Some(match flags & libc::O_ACCMODE {
+        libc::O_RDONLY => (true, false),
+        libc::O_WRONLY => (false, true),
+        _ => (true, true),
+    })
+}
+
+// หาไฟล์ใน /proc/<pid>/maps ซึ่งมีรูปแบบ "address perms offset major:minor inode path"
+fn mapped_handles(proc_path: &Path, targets: &HashSet<FileId>) -> Vec<(FileId, OpenHandle)> {
+    let Ok(maps) = fs::read_to_string(proc_path.join("maps")) else {
+        return Vec::new();
+    };
+    let mut handles: Vec<(FileId, OpenHandle)> = Vec::new();
+
+    for line in maps.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(_), Some(perms), Some(_), Some(device), Some(inode)) = (
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+        ) else {
+            continue;
+        };
+        let Some((major, minor)) = device.split_once(':') else {
+            continue;
+        };
+        let (Ok(major), Ok(minor), Ok(inode)) = (
+            u32::from_str_radix(major, 16),
+            u32::from_str_radix(minor, 16),
+            inode.parse::<u64>(),
+        ) else {
+            continue;
+        };
+        let id = (libc::makedev(major, minor), inode);
+        if !targets.contains(&id) {
+            continue;
+        }
+
+        // ไฟล์เดียวกันมักถูก map หลายช่วง รวมเป็นรายการเดียวต่อไฟล์
+        let index = match handles.iter().position(|(file, _)| *file == id) {
+            Some(index) => index,
+            None => {
+                handles.push((
+                    id,
+                    OpenHandle {
+                        kind: HandleKind::Mmap,
+                        fd: None,
+                        path: fields.collect::<Vec<_>>().join(" "),
+                        read: false,
+                        write: false,
+                        locks: Vec::new(),
+                    },
+                ));
+                handles.len() - 1
+            }
+        };
+        let handle = &mut handles[index].1;
+        handle.read |= perms.starts_with('r');
+        // mapping แบบ private (p) เขียนลงไฟล์ไม่ได้
+        handle.write |= perms.get(1..2) == Some("w") && perms.ends_with('s');
+    }
+
+    handles
+}
+
+// ข้อมูลของ waiter ที่ใช้หา path ของไฟล์
+struct WaitTarget {
+    dev: u64,
+    ino: u64,
+    waiter_pid: u32,
+    holder_pid: u32,
+}
+
+// สร้างรายการ LockWait จาก waiter ใน /proc/locks
+// ถ้าระบุ target จะเลือกเฉพาะ waiter ของไฟล์นั้น
+fn collect_waits(
+    locks: &[ProcLock],
+    target: Option<(u64, u64)>,
+    resolve_path: impl Fn(&WaitTarget) -> String,
+) -> Vec<LockWait> {
+    let mut waits = Vec::new();
+    for lock in locks {
+        if target.is_some_and(|target| (lock.dev, lock.ino) != target) {
+            continue;
+        }
+        let Some(holder) = holder_of(locks, lock) else {
+            continue;
+        };
+        // OFD lock ไม่มี PID จึงระบุไม่ได้ว่าใครรอใคร
+        let (Some(waiter_pid), Some(holder_pid)) = (lock.pid, holder.pid) else {
+            continue;
+        };
+
+        let file_path = resolve_path(&WaitTarget {
+            dev: lock.dev,
+            ino: lock.ino,
+            waiter_pid,
+            holder_pid,
+        });
+
+        waits.push(LockWait {
+            file_path,
+            waiter_pid,
+            waiter_name: process_name(waiter_pid),
+            requested: lock.record.clone(),
+            holder_pid,
+            holder_name: process_name(holder_pid),
+            held: holder.record.clone(),
+        });
+    }
+    waits
+}
+
+// เลือก lock ที่ PID นี้ถืออยู่บนไฟล์ (ไม่รวม lock ที่ยังรออยู่)
+// OFD lock ใน /proc/locks ไม่มี PID จึงใช้ lock ของ fd ที่ process เปิดไฟล์นี้ไว้ (จาก fdinfo)
+// process ที่แค่เปิดไฟล์โดยไม่ได้ถือ OFD lock จึงไม่ได้ lock ของคนอื่นไปด้วย
+fn locks_held_by(
+    locks: &[ProcLock],
+    id: FileId,
+    pid: u32,
+    handles: &[OpenHandle],
+) -> Vec<LockRecord> {
+    let mut held: Vec<LockRecord> = locks
+        .iter()
+        .filter(|lock| !lock.waiting && (lock.dev, lock.ino) == id && lock.pid == Some(pid))
+        .map(|lock| lock.record.clone())
+        .collect();
+
+    for record in handles.iter().flat_map(|handle| &handle.locks) {
+        // fd ที่ dup มาจาก description เดียวกันแสดง lock เดิมซ้ำ
+        if record.class == LockClass::OfdLck && !held.contains(record) {
+            held.push(record.clone());
+        }
+    }
+    held
+}
+
+// ดึงชื่อ process จาก /proc/<pid>/comm
+fn process_name(pid: u32) -> String {
+    fs::read_to_string(Path::new("/proc").join(pid.to_string()).join("comm"))
+        .map(|name| name.trim_end().to_string())
+        .unwrap_or_else(|_| format!("PID {}", pid))
+}
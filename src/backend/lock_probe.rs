@@ -0,0 +1,106 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use super::proc_locks::read_proc_locks;
+use crate::error::FlcError;
+use crate::types::{LockClass, LockConflict, LockMode, LockRecord, LockRequest};
+use std::fs::{self, File};
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::AsRawFd;
+
+// ทดสอบว่าคำขอ lock จะได้หรือไม่ โดยไม่ได้ lock ไฟล์จริง
+pub fn probe(file_path: &str, request: &LockRequest) -> Result<Option<LockConflict>, FlcError> {
+    match request.class {
+        LockClass::Posix => probe_fcntl(file_path, request, libc::F_GETLK),
+        LockClass::OfdLck => probe_fcntl(file_path, request, libc::F_OFD_GETLK),
+        LockClass::Flock => probe_flock(file_path, request),
+        LockClass::Lease => Err(FlcError::unsupported("Lease requests cannot be probed")),
+    }
+}
+
+// ใช้ F_GETLK / F_OFD_GETLK ให้ kernel ตอบว่ามี lock ใดขวางอยู่
+fn probe_fcntl(
+    file_path: &str,
+    request: &LockRequest,
+    command: libc::c_int,
+) -> Result<Option<LockConflict>, FlcError> {
+    // F_GETLK ไม่ต้องการสิทธิ์เขียน จึงเปิดแบบอ่านอย่างเดียวพอ
+    let file = File::open(file_path)
+        .map_err(|e| FlcError::io(format!("Failed to open '{}'", file_path), e))?;
+
+    let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+    lock.l_type = match request.mode {
+        LockMode::Read => libc::F_RDLCK,
+        LockMode::Write => libc::F_WRLCK,
+    } as libc::c_short;
+    lock.l_whence = libc::SEEK_SET as libc::c_short;
+    lock.l_start = to_off(request.start)?;
+    lock.l_len = to_off(request.len)?;
+    // F_OFD_GETLK กำหนดให้ l_pid ต้องเป็น 0
+    lock.l_pid = 0;
+
+    let result = unsafe { libc::fcntl(file.as_raw_fd(), command, &mut lock) };
+    if result == -1 {
+        return Err(FlcError::io(
+            format!("Failed to query lock on '{}'", file_path),
+            io::Error::last_os_error(),
+        ));
+    }
+
+    if lock.l_type == libc::F_UNLCK as libc::c_short {
+        return Ok(None);
+    }
+
+    let class = if lock.l_pid > 0 {
+        LockClass::Posix
+    } else {
+        LockClass::OfdLck
+    };
+    let start = lock.l_start as u64;
+    Ok(Some(LockConflict {
+        pid: u32::try_from(lock.l_pid).ok().filter(|pid| *pid > 0),
+        name: None,
+        record: LockRecord {
+            class,
+            mode: if lock.l_type == libc::F_WRLCK as libc::c_short {
+                LockMode::Write
+            } else {
+                LockMode::Read
+            },
+            mandatory: false,
+            start,
+            end: (lock.l_len > 0).then(|| start + lock.l_len as u64 - 1),
+        },
+    }))
+}
+
+// flock ไม่มีคำสั่งสำหรับถาม จึงดูจาก /proc/locks แทนการลอง lock จริง
+fn probe_flock(file_path: &str, request: &LockRequest) -> Result<Option<LockConflict>, FlcError> {
+    let metadata = fs::metadata(file_path)
+        .map_err(|e| FlcError::io(format!("Failed to stat '{}'", file_path), e))?;
+    let locks = read_proc_locks().map_err(|e| FlcError::io("Failed to read /proc/locks", e))?;
+
+    // flock แบบ shared ขัดแย้งกับ exclusive เท่านั้น ส่วน exclusive ขัดแย้งกับทุก flock
+    let conflict = locks.into_iter().find(|lock| {
+        !lock.waiting
+            && lock.record.class == LockClass::Flock
+            && (lock.dev, lock.ino) == (metadata.dev(), metadata.ino())
+            && (request.mode == LockMode::Write || lock.record.mode == LockMode::Write)
+    });
+
+    Ok(conflict.map(|lock| LockConflict {
+        pid: lock.pid,
+        name: None,
+        record: lock.record,
+    }))
+}
+
+fn to_off(value: u64) -> Result<libc::off_t, FlcError> {
+    libc::off_t::try_from(value).map_err(|e| {
+        FlcError::io(
+            format!("Offset {} is too large", value),
+            io::Error::new(io::ErrorKind::InvalidInput, e),
+        )
+    })
+}
@@ -0,0 +1,84 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use crate::error::FlcError;
+use crate::types::{DeletedFiles, FileLockInfo, LockConflict, LockRequest, LockWait};
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+mod lock_probe;
+#[cfg(target_os = "linux")]
+mod mounts;
+#[cfg(target_os = "linux")]
+pub(crate) mod proc_locks;
+#[cfg(not(any(windows, target_os = "linux")))]
+mod unsupported;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(target_os = "linux")]
+pub use linux::ProcBackend as PlatformBackend;
+#[cfg(not(any(windows, target_os = "linux")))]
+pub use unsupported::UnsupportedBackend as PlatformBackend;
+#[cfg(windows)]
+pub use windows::RestartManagerBackend as PlatformBackend;
+
+// interface กลางของการตรวจสอบ lock ในแต่ละ platform
+pub trait LockBackend {
+    // ตรวจสอบว่าไฟล์ถูก lock หรือไม่ และโดย process ใด
+    fn check_file_locks(&self, file_path: &str) -> Result<FileLockInfo, FlcError>;
+
+    // ตรวจหลายไฟล์พร้อมกัน ได้ผลหนึ่งรายการต่อไฟล์ตามลำดับ
+    // error ด้านนอกคือ backend ใช้ไม่ได้ทั้งหมด ส่วน error ของแต่ละไฟล์อยู่ในรายการ
+    // backend ที่สแกนครั้งเดียวได้ควร override แทนการตรวจทีละไฟล์
+    fn check_many_file_locks(
+        &self,
+        file_paths: &[&str],
+    ) -> Result<Vec<Result<FileLockInfo, FlcError>>, FlcError> {
+        Ok(file_paths
+            .iter()
+            .map(|file_path| self.check_file_locks(file_path))
+            .collect())
+    }
+
+    // ตรวจจาก (device, inode) แทน path สำหรับไฟล์ที่ไม่มี path ให้เข้าถึงแล้ว เช่นถูกลบแต่ยังเปิดอยู่
+    // file_path ของผลแต่ละรายการคือ "DEV:INO"
+    fn check_inode_locks(&self, _ids: &[(u64, u64)]) -> Result<Vec<FileLockInfo>, FlcError> {
+        Err(FlcError::unsupported(
+            "Checking by device and inode is only supported on Linux",
+        ))
+    }
+
+    // ไฟล์ที่ถูกลบแล้วแต่ยังมี process เปิดอยู่ทั้งระบบ
+    fn deleted_files(&self) -> Result<DeletedFiles, FlcError> {
+        Err(FlcError::unsupported(
+            "Finding deleted files that are still open is only supported on Linux",
+        ))
+    }
+
+    // kill process เดียวจาก PID
+    fn kill_process(&self, pid: u32) -> Result<(), FlcError>;
+
+    // ดึง path ของ process จาก PID
+    fn get_process_path(&self, pid: u32) -> String;
+
+    // ดึงชื่อ user ที่เป็นเจ้าของ process
+    fn get_process_user(&self, pid: u32) -> Option<String>;
+
+    // รายการ process ที่กำลังรอ lock ซึ่ง process อื่นถืออยู่ ทั้งระบบ
+    fn lock_waits(&self) -> Result<Vec<LockWait>, FlcError>;
+
+    // ถาม kernel ว่าคำขอ lock จะได้หรือไม่ โดยไม่ lock จริง
+    // คืนค่า None ถ้าได้ lock หรือ lock ที่ขวางอยู่ถ้าไม่ได้
+    fn probe_lock(
+        &self,
+        file_path: &str,
+        request: &LockRequest,
+    ) -> Result<Option<LockConflict>, FlcError>;
+}
+
+// เลือก backend ตาม platform ที่ compile
+pub fn platform() -> PlatformBackend {
+    PlatformBackend
+}
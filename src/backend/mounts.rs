@@ -0,0 +1,90 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use crate::types::Filesystem;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// mount หนึ่งรายการจาก /proc/self/mountinfo
+#[derive(Debug, Clone)]
+pub struct Mount {
+    pub dev: u64,
+    pub filesystem: Filesystem,
+}
+
+// อ่าน /proc/self/mountinfo ซึ่งมีรูปแบบ
+// "id parent major:minor root mount_point options [optional...] - fs_type source super_options"
+pub fn read_mounts() -> io::Result<Vec<Mount>> {
+    let content = fs::read_to_string("/proc/self/mountinfo")?;
+
+    let mut mounts = Vec::new();
+    for line in content.lines() {
+        // field ก่อน "-" มีจำนวนไม่แน่นอน จึงแยกเป็นสองส่วน
+        let Some((before, after)) = line.split_once(" - ") else {
+            continue;
+        };
+        let fields: Vec<&str> = before.split_whitespace().collect();
+        let mut after = after.split_whitespace();
+        let (Some(device), Some(mount_point), Some(fs_type), Some(source)) =
+            (fields.get(2), fields.get(4), after.next(), after.next())
+        else {
+            continue;
+        };
+        let Some((major, minor)) = device.split_once(':') else {
+            continue;
+        };
+        let (Ok(major), Ok(minor)) = (major.parse(), minor.parse()) else {
+            continue;
+        };
+
+        mounts.push(Mount {
+            dev: libc::makedev(major, minor),
+            filesystem: Filesystem {
+                mount_point: unescape(mount_point),
+                fs_type: fs_type.to_string(),
+                source: unescape(source),
+            },
+        });
+    }
+    Ok(mounts)
+}
+
+// หา mount ของไฟล์: เลือก mount ของ device เดียวกันที่ mount point ยาวที่สุดซึ่งครอบ path
+// ถ้า device ไม่ตรงกับ mount ใดเลย (เช่น subvolume ของ btrfs) ใช้ path อย่างเดียว
+pub fn mount_of<'a>(mounts: &'a [Mount], dev: u64, path: &str) -> Option<&'a Mount> {
+    let covering = |mount: &&Mount| Path::new(path).starts_with(&mount.filesystem.mount_point);
+    let longest = |mount: &&Mount| mount.filesystem.mount_point.len();
+
+    let same_device: Vec<&Mount> = mounts.iter().filter(|mount| mount.dev == dev).collect();
+    if same_device.is_empty() {
+        return mounts.iter().filter(covering).max_by_key(longest);
+    }
+    same_device
+        .iter()
+        .copied()
+        .filter(covering)
+        .max_by_key(longest)
+        .or(same_device.first().copied())
+}
+
+// mountinfo เขียนช่องว่าง tab ขึ้นบรรทัดใหม่และ backslash เป็นเลขฐาน 8 เช่น "\040"
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\'
+            && let Some(code) = field
+                .get(i + 1..i + 4)
+                .and_then(|digits| u8::from_str_radix(digits, 8).ok())
+        {
+            out.push(code);
+            i += 4;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
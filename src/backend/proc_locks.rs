@@ -0,0 +1,233 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use crate::types::{LockClass, LockMode, LockRecord};
+use std::fs;
+use std::io;
+
+// lock หนึ่งรายการจาก /proc/locks
+#[derive(Debug, Clone)]
+pub struct ProcLock {
+    // OFD lock ไม่ผูกกับ process จึงไม่มี PID (kernel แสดงเป็น -1)
+    pub pid: Option<u32>,
+    pub dev: u64,
+    pub ino: u64,
+    pub record: LockRecord,
+    // true ถ้าเป็น waiter ที่ยังรอ lock อยู่ (บรรทัดที่มี "->")
+    pub waiting: bool,
+    // index ของ lock ที่ขวาง waiter นี้อยู่
+    pub blocker: Option<usize>,
+}
+
+// อ่านและแปลง /proc/locks ทั้งหมด รวมถึง waiter ที่ถูก block อยู่
+pub fn read_proc_locks() -> io::Result<Vec<ProcLock>> {
+    fs::read_to_string("/proc/locks").map(|content| parse_proc_locks(&content))
+}
+
+fn parse_proc_locks(content: &str) -> Vec<ProcLock> {
+    let mut locks = Vec::new();
+    // lock ล่าสุดในแต่ละระดับ ใช้หาว่า waiter ถูก lock ไหนขวางอยู่
+    let mut parents: Vec<Option<usize>> = Vec::new();
+
+    for line in content.lines() {
+        let depth = waiter_depth(line);
+        parents.truncate(depth);
+        let blocker = depth
+            .checked_sub(1)
+            .and_then(|parent| parents.get(parent).copied().flatten());
+
+        match parse_line(line) {
+            Some(mut lock) => {
+                lock.blocker = blocker;
+                parents.push(Some(locks.len()));
+                locks.push(lock);
+            }
+            None => parents.push(None),
+        }
+    }
+
+    locks
+}
+
+// lock ที่ถืออยู่จริงซึ่งขวาง waiter นี้ โดยไล่ blocker ขึ้นไปจนถึง lock ที่ไม่ได้รอ
+// waiter ที่ซ้อนกันอาจถูกขวางโดย waiter อีกตัว ซึ่งยังไม่ได้ถือ lock ที่ขอไว้
+pub fn holder_of<'a>(locks: &'a [ProcLock], lock: &ProcLock) -> Option<&'a ProcLock> {
+    let mut blocker = &locks[lock.blocker?];
+    // blocker อยู่ก่อน waiter ใน /proc/locks เสมอ จึงไม่วนซ้ำ
+    while blocker.waiting {
+        blocker = &locks[blocker.blocker?];
+    }
+    Some(blocker)
+}
+
+// lock ที่ผูกกับ open file description ของ fd จากบรรทัด "lock:" ใน /proc/<pid>/fdinfo/<fd>
+// ใช้หาเจ้าของ OFD lock ซึ่ง /proc/locks แสดง PID เป็น -1
+pub fn parse_fd_locks(fdinfo: &str) -> Vec<ProcLock> {
+    fdinfo
+        .lines()
+        .filter_map(|line| line.strip_prefix("lock:"))
+        .filter_map(|line| parse_line(line.trim_start()))
+        .collect()
+}
+
+// ระดับการซ้อนของ waiter kernel พิมพ์ "->" ครั้งเดียวแล้วเยื้องด้วยช่องว่าง
+// เช่น "1: -> " คือระดับ 1 และ "1:  -> " คือระดับ 2 ส่วน lock ที่ถืออยู่คือระดับ 0
+fn waiter_depth(line: &str) -> usize {
+    let Some((_, rest)) = line.split_once(':') else {
+        return 0;
+    };
+    let arrow = rest.trim_start_matches(' ');
+    if arrow.starts_with("->") {
+        rest.len() - arrow.len()
+    } else {
+        0
+    }
+}
+
+// แปลงบรรทัดเช่น "1: POSIX  ADVISORY  WRITE 1234 08:01:5678 0 EOF"
+// บรรทัดที่ไม่รู้จัก (เช่น DELEG) จะถูกข้าม
+fn parse_line(line: &str) -> Option<ProcLock> {
+    // ข้ามลำดับของ lock เช่น "1:"
+    let mut fields = line.split_whitespace().skip(1).peekable();
+    let waiting = fields.next_if_eq(&"->").is_some();
+
+    let class = match fields.next()? {
+        "POSIX" => LockClass::Posix,
+        "FLOCK" => LockClass::Flock,
+        "OFDLCK" => LockClass::OfdLck,
+        "LEASE" => LockClass::Lease,
+        _ => return None,
+    };
+    let mandatory = fields.next()? == "MANDATORY";
+    let mode = match fields.next()? {
+        "READ" => LockMode::Read,
+        "WRITE" => LockMode::Write,
+        _ => return None,
+    };
+    let pid = fields.next()?.parse::<i64>().ok()?;
+
+    // รูปแบบ device เป็น "major:minor:inode" โดย major/minor เป็นเลขฐาน 16
+    let mut id = fields.next()?.split(':');
+    let major = u32::from_str_radix(id.next()?, 16).ok()?;
+    let minor = u32::from_str_radix(id.next()?, 16).ok()?;
+    let ino = id.next()?.parse().ok()?;
+
+    let start = fields.next()?.parse().ok()?;
+    let end = match fields.next()? {
+        "EOF" => None,
+        end => Some(end.parse().ok()?),
+    };
+
+    Some(ProcLock {
+        pid: u32::try_from(pid).ok(),
+        dev: libc::makedev(major, minor),
+        ino,
+        record: LockRecord {
+            class,
+            mode,
+            mandatory,
+            start,
+            end,
+        },
+        waiting,
+        blocker: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_held_lock() {
+        let lock = parse_line("1: POSIX  ADVISORY  WRITE 1234 08:01:5678 0 EOF").unwrap();
+        assert_eq!(lock.pid, Some(1234));
+        assert_eq!(lock.dev, libc::makedev(8, 1));
+        assert_eq!(lock.ino, 5678);
+        assert_eq!(lock.record.class, LockClass::Posix);
+        assert_eq!(lock.record.mode, LockMode::Write);
+        assert!(!lock.record.mandatory);
+        assert_eq!((lock.record.start, lock.record.end), (0, None));
+        assert!(!lock.waiting);
+    }
+
+    #[test]
+    fn parses_waiter() {
+        let lock = parse_line("2: -> FLOCK  ADVISORY  READ 99 fe:00:42 0 EOF").unwrap();
+        assert!(lock.waiting);
+        assert_eq!(lock.pid, Some(99));
+        assert_eq!(lock.record.class, LockClass::Flock);
+        assert_eq!(lock.record.mode, LockMode::Read);
+    }
+
+    #[test]
+    fn skips_unknown_lines() {
+        assert!(parse_line("3: DELEG  ACTIVE    READ 1 08:01:5 0 EOF").is_none());
+        assert!(parse_line("garbage").is_none());
+    }
+
+    #[test]
+    fn waiter_depth_follows_indentation() {
+        assert_eq!(waiter_depth("1: POSIX  ADVISORY  WRITE 1 08:01:5 0 EOF"), 0);
+        assert_eq!(
+            waiter_depth("1: -> POSIX  ADVISORY  WRITE 2 08:01:5 0 EOF"),
+            1
+        );
+        assert_eq!(
+            waiter_depth("1:  -> POSIX  ADVISORY  WRITE 3 08:01:5 0 EOF"),
+            2
+        );
+        assert_eq!(
+            waiter_depth("1:   -> POSIX  ADVISORY  WRITE 4 08:01:5 0 EOF"),
+            3
+        );
+    }
+
+    #[test]
+    fn nested_waiters_point_at_their_blocker() {
+        let content = "1: POSIX  ADVISORY  WRITE 10 08:01:5 0 EOF\n\
+                       1: -> POSIX  ADVISORY  WRITE 20 08:01:5 0 EOF\n\
+                       1:  -> POSIX  ADVISORY  WRITE 30 08:01:5 0 EOF\n\
+                       1: -> POSIX  ADVISORY  READ 40 08:01:5 0 EOF\n\
+                       2: FLOCK  ADVISORY  WRITE 50 08:01:6 0 EOF\n";
+        let locks = parse_proc_locks(content);
+        let blockers: Vec<Option<usize>> = locks.iter().map(|lock| lock.blocker).collect();
+        assert_eq!(blockers, [None, Some(0), Some(1), Some(0), None]);
+        assert!(locks[2].waiting && !locks[4].waiting);
+    }
+
+    #[test]
+    fn deep_waiters_resolve_to_the_real_holder() {
+        let content = "1: POSIX  ADVISORY  WRITE 10 08:01:5 0 EOF\n\
+                       1: -> POSIX  ADVISORY  WRITE 20 08:01:5 0 EOF\n\
+                       1:  -> POSIX  ADVISORY  WRITE 30 08:01:5 0 EOF\n\
+                       1:   -> POSIX  ADVISORY  WRITE 40 08:01:5 0 EOF\n";
+        let locks = parse_proc_locks(content);
+        assert_eq!(locks[3].blocker, Some(2));
+        for waiter in &locks[1..] {
+            assert_eq!(
+                holder_of(&locks, waiter).and_then(|holder| holder.pid),
+                Some(10)
+            );
+        }
+        assert!(holder_of(&locks, &locks[0]).is_none());
+    }
+
+    #[test]
+    fn fd_locks_keep_ofd_locks_without_pid() {
+        let fdinfo = "pos:\t0\nflags:\t02\nmnt_id:\t29\nino:\t1222833\n\
+                      lock:\t1: OFDLCK ADVISORY  WRITE -1 fe:00:1222833 0 3\n";
+        let locks = parse_fd_locks(fdinfo);
+        assert_eq!(locks.len(), 1);
+        assert_eq!(locks[0].pid, None);
+        assert_eq!(locks[0].ino, 1222833);
+        assert_eq!(locks[0].record.class, LockClass::OfdLck);
+        assert_eq!(locks[0].record.mode, LockMode::Write);
+        assert_eq!((locks[0].record.start, locks[0].record.end), (0, Some(3)));
+    }
+
+    #[test]
+    fn fd_without_locks_has_none() {
+        assert!(parse_fd_locks("pos:\t0\nflags:\t0100000\n").is_empty());
+    }
+}
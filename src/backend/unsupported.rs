@@ -0,0 +1,47 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use super::LockBackend;
+use crate::error::FlcError;
+use crate::types::{FileLockInfo, LockConflict, LockRequest, LockWait};
+
+// backend สำรองสำหรับ platform ที่ยังไม่รองรับ
+pub struct UnsupportedBackend;
+
+impl LockBackend for UnsupportedBackend {
+    fn check_file_locks(&self, _file_path: &str) -> Result<FileLockInfo, FlcError> {
+        Err(FlcError::unsupported(
+            "File lock detection is not supported on this platform",
+        ))
+    }
+
+    fn kill_process(&self, _pid: u32) -> Result<(), FlcError> {
+        Err(FlcError::unsupported(
+            "Killing processes is not supported on this platform",
+        ))
+    }
+
+    fn get_process_path(&self, pid: u32) -> String {
+        format!("Unknown (PID: {})", pid)
+    }
+
+    fn get_process_user(&self, _pid: u32) -> Option<String> {
+        None
+    }
+
+    fn lock_waits(&self) -> Result<Vec<LockWait>, FlcError> {
+        Err(FlcError::unsupported(
+            "Lock wait information is not supported on this platform",
+        ))
+    }
+
+    fn probe_lock(
+        &self,
+        _file_path: &str,
+        _request: &LockRequest,
+    ) -> Result<Option<LockConflict>, FlcError> {
+        Err(FlcError::unsupported(
+            "Lock probing is not supported on this platform",
+        ))
+    }
+}
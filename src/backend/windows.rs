@@ -0,0 +1,433 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use super::LockBackend;
+use crate::error::FlcError;
+use crate::types::{FileLockInfo, LockConflict, LockRequest, LockWait, ProcessInfo};
+use crate::utils::wide_string_to_string;
+use std::ffi::OsString;
+use std::io;
+use std::os::windows::ffi::OsStrExt;
+use std::ptr;
+use winapi::shared::minwindef::{DWORD, FALSE};
+use winapi::shared::winerror::{ERROR_ACCESS_DENIED, ERROR_INVALID_PARAMETER, ERROR_MORE_DATA};
+use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+use winapi::um::processthreadsapi::{OpenProcess, OpenProcessToken, TerminateProcess};
+use winapi::um::psapi::GetProcessImageFileNameW;
+use winapi::um::restartmanager::*;
+use winapi::um::securitybaseapi::GetTokenInformation;
+use winapi::um::winbase::LookupAccountSidW;
+use winapi::um::winnt::{
+    HANDLE, PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE,
+    PROCESS_VM_READ, SID_NAME_USE, TOKEN_QUERY, TOKEN_USER, TokenUser,
+};
+
+// backend ที่ใช้ Windows Restart Manager API
+pub struct RestartManagerBackend;
+
+impl LockBackend for RestartManagerBackend {
+    fn check_file_locks(&self, file_path: &str) -> Result<FileLockInfo, FlcError> {
+        let processes = self.session_processes(&[file_path])?;
+        Ok(lock_info(file_path, processes))
+    }
+
+    // RmGetList รายงานรวมทุกไฟล์ที่ลงทะเบียนใน session โดยไม่บอกว่า process ใช้ไฟล์ไหน
+    // จึงลงทะเบียนทุกไฟล์ใน session เดียวก่อน ถ้าไม่มีใครใช้เลยก็จบในครั้งเดียว
+    // ถ้ามีจึงแบ่งครึ่งไปเรื่อย ๆ จนรู้ว่าแต่ละ process ใช้ไฟล์ไหน
+    fn check_many_file_locks(
+        &self,
+        file_paths: &[&str],
+    ) -> Result<Vec<Result<FileLockInfo, FlcError>>, FlcError> {
+        let mut results = Vec::with_capacity(file_paths.len());
+        bisect(
+            file_paths,
+            &|file_paths| self.session_processes(file_paths),
+            &mut results,
+        );
+        Ok(results)
+    }
+
+    fn kill_process(&self, pid: u32) -> Result<(), FlcError> {
+        unsafe {
+            // เปิด handle ของ process พร้อม permission ในการ terminate
+            let handle = OpenProcess(PROCESS_TERMINATE, FALSE, pid);
+            if handle == ptr::null_mut() || handle == INVALID_HANDLE_VALUE {
+                return Err(process_error(pid, io::Error::last_os_error()));
+            }
+
+            // ทำการ terminate process
+            let result = TerminateProcess(handle, 1);
+            let error = io::Error::last_os_error();
+            CloseHandle(handle);
+
+            if result == 0 {
+                Err(process_error(pid, error))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn get_process_path(&self, pid: u32) -> String {
+        unsafe {
+            // เปิด handle ของ process
+            let handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, FALSE, pid);
+            if handle == ptr::null_mut() || handle == INVALID_HANDLE_VALUE {
+                return format!("Unknown (PID: {})", pid);
+            }
+
+            // ดึง path ของ executable
+            let mut buffer = [0u16; 1024];
+            let result = GetProcessImageFileNameW(handle, buffer.as_mut_ptr(), buffer.len() as u32);
+
+            CloseHandle(handle);
+
+            if result > 0 {
+                wide_string_to_string(&buffer[..result as usize])
+            } else {
+                format!("Unknown (PID: {})", pid)
+            }
+        }
+    }
+
+    fn get_process_user(&self, pid: u32) -> Option<String> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, pid);
+            if process == ptr::null_mut() || process == INVALID_HANDLE_VALUE {
+                return None;
+            }
+
+            let mut token: HANDLE = ptr::null_mut();
+            let opened = OpenProcessToken(process, TOKEN_QUERY, &mut token);
+            CloseHandle(process);
+            if opened == 0 {
+                return None;
+            }
+
+            // TOKEN_USER มี SID ต่อท้าย จึงต้องใช้ buffer ขนาดตามที่ระบบบอก
+            // (ใช้ u64 เพื่อให้ alignment พอสำหรับ pointer ใน TOKEN_USER)
+            let mut needed: DWORD = 0;
+            GetTokenInformation(token, TokenUser, ptr::null_mut(), 0, &mut needed);
+            let mut buffer = vec![0u64; (needed as usize).div_ceil(8)];
+            let result = GetTokenInformation(
+                token,
+                TokenUser,
+                buffer.as_mut_ptr() as *mut _,
+                needed,
+                &mut needed,
+            );
+            CloseHandle(token);
+            if result == 0 {
+                return None;
+            }
+            let token_user = &*(buffer.as_ptr() as *const TOKEN_USER);
+
+            let mut name = [0u16; 256];
+            let mut name_len = name.len() as DWORD;
+            let mut domain = [0u16; 256];
+            let mut domain_len = domain.len() as DWORD;
+            let mut sid_type: SID_NAME_USE = 0;
+            let result = LookupAccountSidW(
+                ptr::null(),
+                token_user.User.Sid,
+                name.as_mut_ptr(),
+                &mut name_len,
+                domain.as_mut_ptr(),
+                &mut domain_len,
+                &mut sid_type,
+            );
+            if result == 0 {
+                return None;
+            }
+
+            let name = wide_string_to_string(&name[..name_len as usize]);
+            let domain = wide_string_to_string(&domain[..domain_len as usize]);
+            if domain.is_empty() {
+                Some(name)
+            } else {
+                Some(format!("{}\\{}", domain, name))
+            }
+        }
+    }
+
+    fn lock_waits(&self) -> Result<Vec<LockWait>, FlcError> {
+        Err(FlcError::unsupported(
+            "Lock wait information is not available from Restart Manager",
+        ))
+    }
+
+    fn probe_lock(
+        &self,
+        _file_path: &str,
+        _request: &LockRequest,
+    ) -> Result<Option<LockConflict>, FlcError> {
+        Err(FlcError::unsupported(
+            "Lock probing is not supported on Windows",
+        ))
+    }
+}
+
+impl RestartManagerBackend {
+    // เปิด Restart Manager session ที่ลงทะเบียนทุกไฟล์ แล้วคืน process ที่ใช้ไฟล์ใดไฟล์หนึ่งอยู่
+    // หมายเหตุ: Restart Manager API อาจไม่ครอบคลุม kernel-level locks และ system services
+    fn session_processes(&self, file_paths: &[&str]) -> Result<Vec<ProcessInfo>, FlcError> {
+        unsafe {
+            let mut session_handle: DWORD = 0;
+            let mut session_key = [0u16; CCH_RM_SESSION_KEY + 1];
+
+            // เริ่มต้น Restart Manager session
+            let result = RmStartSession(&mut session_handle, 0, session_key.as_mut_ptr());
+            if result != 0 {
+                return Err(rm_error("Failed to start Restart Manager session", result));
+            }
+
+            // แปลง file path เป็น wide string สำหรับ Windows API
+            let wide_paths: Vec<Vec<u16>> = file_paths
+                .iter()
+                .map(|file_path| {
+                    OsString::from(*file_path)
+                        .encode_wide()
+                        .chain(std::iter::once(0))
+                        .collect()
+                })
+                .collect();
+
+            // ลงทะเบียน resource (ไฟล์) ทั้งหมดที่ต้องการตรวจสอบ
+            let mut files: Vec<*const u16> = wide_paths.iter().map(|path| path.as_ptr()).collect();
+            let result = RmRegisterResources(
+                session_handle,
+                files.len() as u32, // จำนวนไฟล์
+                files.as_mut_ptr(), // array ของไฟล์
+                0,                  // จำนวน services
+                ptr::null_mut(),    // array ของ services
+                0,                  // จำนวน processes
+                ptr::null_mut(),    // array ของ processes
+            );
+
+            if result != 0 {
+                RmEndSession(session_handle);
+                let action = match file_paths {
+                    [file_path] => {
+                        format!("Failed to register '{}' with Restart Manager", file_path)
+                    }
+                    _ => format!(
+                        "Failed to register {} files with Restart Manager",
+                        file_paths.len()
+                    ),
+                };
+                return Err(rm_error(&action, result));
+            }
+
+            // ดึงรายการ applications ที่ใช้ resource นี้
+            let mut proc_info_needed: DWORD = 0;
+            let mut proc_info_count: DWORD = 0;
+            let mut reboot_reason: DWORD = 0;
+
+            // เรียกครั้งแรกเพื่อดูว่าต้องการ buffer ขนาดเท่าไร
+            let result = RmGetList(
+                session_handle,
+                &mut proc_info_needed,
+                &mut proc_info_count,
+                ptr::null_mut(),
+                &mut reboot_reason,
+            );
+
+            let mut processes = Vec::new();
+
+            // ถ้ามี data มากกว่า buffer ที่เตรียมไว้
+            if result == ERROR_MORE_DATA && proc_info_needed > 0 {
+                // จัดสรร buffer และดึง data จริง
+                let mut proc_info: Vec<RM_PROCESS_INFO> =
+                    vec![std::mem::zeroed(); proc_info_needed as usize];
+                proc_info_count = proc_info_needed;
+
+                let result = RmGetList(
+                    session_handle,
+                    &mut proc_info_needed,
+                    &mut proc_info_count,
+                    proc_info.as_mut_ptr(),
+                    &mut reboot_reason,
+                );
+
+                if result == 0 {
+                    // แปลงข้อมูล process เป็น struct ที่เราใช้
+                    for i in 0..proc_info_count {
+                        let proc = &proc_info[i as usize];
+                        let process_name = wide_string_to_string(&proc.strAppName);
+                        let process_path = self.get_process_path(proc.Process.dwProcessId);
+
+                        processes.push(ProcessInfo {
+                            pid: proc.Process.dwProcessId,
+                            name: process_name,
+                            path: process_path,
+                            user: self.get_process_user(proc.Process.dwProcessId),
+                            locks: Vec::new(),
+                            handles: Vec::new(),
+                        });
+                    }
+                }
+            }
+
+            // ปิด Restart Manager session
+            RmEndSession(session_handle);
+
+            Ok(processes)
+        }
+    }
+}
+
+// ลงทะเบียนกลุ่มของไฟล์ใน Restart Manager session หนึ่ง session แล้วคืน process ที่ใช้ไฟล์ใดก็ได้ในกลุ่ม
+type Session<'a> = dyn Fn(&[&str]) -> Result<Vec<ProcessInfo>, FlcError> + 'a;
+
+// ตรวจกลุ่มของไฟล์ แล้วเพิ่มผลลง results ตามลำดับเดิม
+// ถ้าทั้งกลุ่มว่างจะจบใน session เดียว ไม่เช่นนั้นจึงแบ่งกลุ่มด้วย split
+fn bisect(
+    file_paths: &[&str],
+    session: &Session,
+    results: &mut Vec<Result<FileLockInfo, FlcError>>,
+) {
+    if file_paths.is_empty() {
+        return;
+    }
+    settle(file_paths, session(file_paths), session, results);
+}
+
+// ใช้ผลของ session ที่ตรวจกลุ่มนี้แล้ว: กลุ่มว่างหรือมีไฟล์เดียวได้ผลทันที กลุ่มอื่นแบ่งต่อ
+fn settle(
+    file_paths: &[&str],
+    result: Result<Vec<ProcessInfo>, FlcError>,
+    session: &Session,
+    results: &mut Vec<Result<FileLockInfo, FlcError>>,
+) {
+    match result {
+        Ok(processes) if processes.is_empty() => results.extend(
+            file_paths
+                .iter()
+                .map(|file_path| Ok(lock_info(file_path, Vec::new()))),
+        ),
+        Ok(processes) if file_paths.len() == 1 => {
+            results.push(Ok(lock_info(file_paths[0], processes)));
+        }
+        Err(e) if file_paths.len() == 1 => results.push(Err(e)),
+        _ => split(file_paths, session, results),
+    }
+}
+
+// แบ่งกลุ่มที่มีคนใช้หรือ error ออกเป็นสองครึ่ง ถ้ามีเพียงครึ่งเดียวที่ไม่ว่างก็แบ่งครึ่งนั้นต่อ
+// ถ้าทั้งสองครึ่งไม่ว่าง การแบ่งต่อไม่ช่วยแยก holder และจะใช้ session มากกว่าตรวจทีละไฟล์
+// จึงตรวจทีละไฟล์แทน (ครึ่งที่มีไฟล์เดียวใช้ผลที่ได้แล้ว)
+// กรณีแย่ที่สุดจึงใช้ไม่เกินหนึ่ง session ต่อไฟล์ บวกสอง session ต่อการแบ่งหนึ่งระดับ
+fn split(
+    file_paths: &[&str],
+    session: &Session,
+    results: &mut Vec<Result<FileLockInfo, FlcError>>,
+) {
+    let (left, right) = file_paths.split_at(file_paths.len() / 2);
+    let left_result = session(left);
+    let right_result = session(right);
+
+    let busy = |result: &Result<Vec<ProcessInfo>, FlcError>| {
+        !result.as_ref().is_ok_and(|processes| processes.is_empty())
+    };
+    let separated = !(busy(&left_result) && busy(&right_result));
+    for (half, result) in [(left, left_result), (right, right_result)] {
+        if separated || half.len() == 1 {
+            settle(half, result, session, results);
+        } else {
+            results.extend(half.iter().map(|&file_path| {
+                session(&[file_path]).map(|processes| lock_info(file_path, processes))
+            }));
+        }
+    }
+}
+
+fn lock_info(file_path: &str, processes: Vec<ProcessInfo>) -> FileLockInfo {
+    FileLockInfo {
+        file_path: file_path.to_string(),
+        processes,
+        waiters: Vec::new(),
+        // Restart Manager ไม่ต้องเปิด process จึงไม่มี process ที่ตรวจไม่ได้
+        inaccessible: Vec::new(),
+    }
+}
+
+// แปลง error code ของ Restart Manager เป็น FlcError
+fn rm_error(action: &str, code: DWORD) -> FlcError {
+    if code == ERROR_ACCESS_DENIED {
+        FlcError::PermissionDenied {
+            action: action.to_string(),
+            code: Some(code as i32),
+        }
+    } else {
+        FlcError::BackendUnavailable {
+            reason: action.to_string(),
+            code: Some(code as i32),
+        }
+    }
+}
+
+// แปลง error จาก OpenProcess / TerminateProcess เป็น FlcError
+fn process_error(pid: u32, error: io::Error) -> FlcError {
+    match error.raw_os_error().map(|code| code as u32) {
+        // OpenProcess คืน ERROR_INVALID_PARAMETER เมื่อไม่มี PID นี้แล้ว
+        Some(ERROR_INVALID_PARAMETER) => FlcError::ProcessGone { pid },
+        Some(ERROR_ACCESS_DENIED) => FlcError::PermissionDenied {
+            action: format!("terminating process {}", pid),
+            code: error.raw_os_error(),
+        },
+        _ => FlcError::io(format!("Failed to terminate process {}", pid), error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    // ไฟล์ที่ชื่อขึ้นต้นด้วย "held" ถูก process ที่มี PID เท่ากับลำดับของไฟล์ใช้อยู่
+    fn check(file_paths: &[&str]) -> (Vec<Vec<u32>>, usize) {
+        let sessions = Cell::new(0);
+        let session = |group: &[&str]| {
+            sessions.set(sessions.get() + 1);
+            Ok(group
+                .iter()
+                .filter(|file_path| file_path.starts_with("held"))
+                .map(|file_path| ProcessInfo {
+                    pid: file_paths.iter().position(|p| p == file_path).unwrap() as u32,
+                    ..Default::default()
+                })
+                .collect())
+        };
+        let mut results = Vec::new();
+        bisect(file_paths, &session, &mut results);
+        let pids = results
+            .into_iter()
+            .map(|result| result.unwrap().processes.iter().map(|p| p.pid).collect())
+            .collect();
+        (pids, sessions.get())
+    }
+
+    #[test]
+    fn free_files_take_one_session() {
+        let files: Vec<String> = (0..100).map(|i| format!("free{}", i)).collect();
+        let files: Vec<&str> = files.iter().map(String::as_str).collect();
+        let (pids, sessions) = check(&files);
+        assert_eq!(pids.len(), 100);
+        assert_eq!(sessions, 1);
+    }
+
+    #[test]
+    fn holders_are_matched_to_their_files() {
+        let (pids, _) = check(&["free0", "held1", "free2", "free3", "held4"]);
+        assert_eq!(pids, [vec![], vec![1], vec![], vec![], vec![4]]);
+    }
+
+    #[test]
+    fn held_files_cost_about_one_session_each() {
+        let files: Vec<String> = (0..64).map(|i| format!("held{}", i)).collect();
+        let files: Vec<&str> = files.iter().map(String::as_str).collect();
+        let (pids, sessions) = check(&files);
+        assert_eq!(pids.len(), 64);
+        assert!(sessions <= 64 + 3, "{} sessions", sessions);
+    }
+}
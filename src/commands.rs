@@ -1,49 +1,504 @@
 // Copyright (c) 2025 Phumin Maliwan
 // SPDX-License-Identifier: MIT
 
-use crate::process::{check_file_locks, kill_processes, processes_changed};
-use crate::types::{FileLockInfo, ProcessInfo};
-use crate::utils::test_file_access;
-use std::io::{self, Write};
+use crate::compat::{self, Compat};
+use crate::delimited::{Column, render_delimited};
+use crate::dot::render_dot;
+use crate::ranges::print_range_map;
+use crate::template::Template;
+use crate::tree::render_tree;
+use file_lock_checker::deadlock::find_cycles;
+use file_lock_checker::explain::explain as explain_file;
+use file_lock_checker::probe::{AccessMode, DenialClass, probe_access};
+use file_lock_checker::report::{
+    Document, FileResult, KillOutcome, MonitorChange, Report, UnlockReport, monitor_events,
+};
+use file_lock_checker::{
+    DeletedFiles, FileLockInfo, FlcError, LockRequest, ProcessInfo, check_file_locks,
+    check_inode_locks, check_many_file_locks, deleted_files, kill_process, lock_waits, probe_lock,
+    processes_changed,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::process::ExitCode;
+use std::sync::OnceLock;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+// รูปแบบการแสดงผลของคำสั่ง check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Dot,
+    Json,
+    // JSON หนึ่ง object ต่อบรรทัด
+    Ndjson,
+    Csv,
+    Tsv,
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use clap::ValueEnum;
+        match self.to_possible_value() {
+            Some(value) => f.pad(value.get_name()),
+            None => Ok(()),
+        }
+    }
+}
+
+// ระดับรายละเอียดของข้อความ จาก --quiet และ --verbose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    // ไม่แสดง banner และคำแนะนำ
+    Quiet,
+    Normal,
+    // แสดงผลการทดสอบการเข้าถึงทุกแบบเพิ่ม
+    Verbose,
+}
+
+static VERBOSITY: OnceLock<Verbosity> = OnceLock::new();
+
+// ตั้งระดับรายละเอียดครั้งเดียวตอนเริ่มโปรแกรม
+pub fn set_verbosity(verbosity: Verbosity) {
+    let _ = VERBOSITY.set(verbosity);
+}
+
+fn verbosity() -> Verbosity {
+    VERBOSITY.get().copied().unwrap_or(Verbosity::Normal)
+}
+
+// exit code ของคำสั่ง เพื่อให้ pipeline แยกกรณีได้
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    // ไม่มี process ใด lock ไฟล์
+    Unlocked = 0,
+    Locked = 1,
+    // ไม่พบ lock แต่มี process ที่ตรวจไม่ได้เพราะไม่มีสิทธิ์
+    Partial = 2,
+    Error = 3,
+}
+
+impl Status {
+    // holder ที่พบแล้วถือว่า lock แน่นอน แม้จะตรวจบาง process ไม่ได้
+    pub fn of(lock_info: &FileLockInfo) -> Status {
+        if !lock_info.processes.is_empty() {
+            Status::Locked
+        } else if !lock_info.inaccessible.is_empty() {
+            Status::Partial
+        } else {
+            Status::Unlocked
+        }
+    }
+
+    // ผลของการรอ: ไม่เหลือ holder คือสำเร็จแม้จะตรวจบาง process ไม่ได้ ส่วน process เหล่านั้นแค่แจ้งไว้
+    pub fn after_wait(lock_info: &FileLockInfo) -> Status {
+        if lock_info.processes.is_empty() {
+            Status::Unlocked
+        } else {
+            Status::Locked
+        }
+    }
+
+    // รวมผลของหลายไฟล์: ไฟล์ที่ถูก lock สำคัญที่สุด ตามด้วย error แล้วจึงผลที่ไม่ครบ
+    pub fn merge(self, other: Status) -> Status {
+        let rank = |status| match status {
+            Status::Unlocked => 0,
+            Status::Partial => 1,
+            Status::Error => 2,
+            Status::Locked => 3,
+        };
+        if rank(other) > rank(self) {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+// ตัวเลือกการแสดงผลของคำสั่ง check
+pub struct CheckOptions {
+    pub format: OutputFormat,
+    // รอกด enter หลังแสดงผลแบบ text (ปิดด้วย --no-pause หรือเมื่อ stdin ไม่ใช่ terminal)
+    pub pause: bool,
+    // คอลัมน์ของ CSV/TSV
+    pub columns: Vec<Column>,
+    // template ที่ผู้ใช้กำหนด ใช้แทน format
+    pub template: Option<Template>,
+    // output แบบ lsof หรือ fuser ใช้แทน format
+    pub compat: Option<Compat>,
+    // แสดง tree ของ path ที่ถูกใช้ต่อจากผลของ check --recursive
+    pub tree: bool,
+}
 
 // คำสั่งตรวจสอบไฟล์ที่ถูก lock
-pub fn check(file_path: &str) {
-    match check_file_locks(file_path) {
-        Ok(lock_info) => {
-            print_lock_info(&lock_info);
+pub fn check(file_path: &str, options: &CheckOptions) -> Status {
+    // template ใช้ใน script จึงไม่ต้องรอกด enter เช่นเดียวกับ format อื่นที่ไม่ใช่ text
+    if let Some(template) = &options.template {
+        return match check_file_locks(file_path) {
+            Ok(lock_info) => {
+                print!("{}", template.render(&lock_info));
+                Status::of(&lock_info)
+            }
+            Err(e) => {
+                eprintln!("Error checking file locks: {}", e);
+                Status::Error
+            }
+        };
+    }
+    if let Some(mode) = options.compat {
+        return match check_file_locks(file_path) {
+            Ok(lock_info) => {
+                print!("{}", compat::render(mode, std::slice::from_ref(&lock_info)));
+                Status::of(&lock_info)
+            }
+            Err(e) => {
+                eprintln!("Error checking file locks: {}", e);
+                Status::Error
+            }
+        };
+    }
+
+    let format = options.format;
+    let status = match check_file_locks(file_path) {
+        Ok(lock_info) => match format {
+            OutputFormat::Text => {
+                print_lock_info(&lock_info);
+                Status::of(&lock_info)
+            }
+            OutputFormat::Dot => {
+                // output ที่เป็น graph มักถูก pipe ต่อ จึงไม่ต้องรอกด enter
+                print!("{}", render_dot(std::slice::from_ref(&lock_info)));
+                return Status::of(&lock_info);
+            }
+            OutputFormat::Json => {
+                print_json(Report::Check(&lock_info));
+                return Status::of(&lock_info);
+            }
+            OutputFormat::Ndjson => {
+                print_json_line(Report::Check(&lock_info));
+                return Status::of(&lock_info);
+            }
+            OutputFormat::Csv | OutputFormat::Tsv => {
+                let separator = if format == OutputFormat::Csv {
+                    ','
+                } else {
+                    '\t'
+                };
+                print!(
+                    "{}",
+                    render_delimited(
+                        std::slice::from_ref(&lock_info),
+                        &options.columns,
+                        separator
+                    )
+                );
+                return Status::of(&lock_info);
+            }
+        },
+        Err(e) if format == OutputFormat::Ndjson => {
+            print_json_line(Report::Error {
+                file_path: Some(file_path),
+                error: &e,
+            });
+            return Status::Error;
+        }
+        Err(e) if format == OutputFormat::Json => {
+            print_json_error(file_path, &e);
+            return Status::Error;
         }
         Err(e) => {
             eprintln!("Error checking file locks: {}", e);
+            Status::Error
+        }
+    };
+
+    pause(options);
+    status
+}
+
+// คำสั่ง check หลายไฟล์ ตรวจด้วยการสแกนครั้งเดียวแล้วแสดงผลของทุกไฟล์รวมกัน
+// ไฟล์ที่ตรวจไม่ได้ไม่ทำให้ไฟล์อื่นหยุดตรวจ
+// roots คือ directory ของ check --recursive: แสดงเฉพาะ path ที่ถูกใช้ และแบบ text จะจัดกลุ่มตาม process
+pub fn check_many(
+    file_paths: &[String],
+    roots: Option<&[String]>,
+    options: &CheckOptions,
+) -> Status {
+    let paths: Vec<&str> = file_paths.iter().map(String::as_str).collect();
+    print_many_results(&paths, check_many_file_locks(&paths), roots, options)
+}
+
+// คำสั่ง check --inode สำหรับไฟล์ที่ไม่มี path แล้ว ตรวจจาก (device, inode) โดยตรง
+// แสดงผลแบบหลายไฟล์เสมอ โดยใช้ "DEV:INO" แทน path
+pub fn check_inodes(ids: &[(u64, u64)], options: &CheckOptions) -> Status {
+    let labels: Vec<String> = ids
+        .iter()
+        .map(|(dev, ino)| format!("{}:{}", dev, ino))
+        .collect();
+    let paths: Vec<&str> = labels.iter().map(String::as_str).collect();
+    let results = check_inode_locks(ids).map(|lock_infos| lock_infos.into_iter().map(Ok).collect());
+    print_many_results(&paths, results, None, options)
+}
+
+// แสดงผลของการตรวจหลายไฟล์ตาม format ที่เลือก และคืน Status รวม
+fn print_many_results(
+    paths: &[&str],
+    results: Result<Vec<Result<FileLockInfo, FlcError>>, FlcError>,
+    roots: Option<&[String]>,
+    options: &CheckOptions,
+) -> Status {
+    let format = options.format;
+    let results = match results {
+        Ok(results) => results,
+        Err(e) => {
+            match format {
+                OutputFormat::Json => print_json(Report::Error {
+                    file_path: None,
+                    error: &e,
+                }),
+                OutputFormat::Ndjson => print_json_line(Report::Error {
+                    file_path: None,
+                    error: &e,
+                }),
+                _ => eprintln!("Error checking file locks: {}", e),
+            }
+            return Status::Error;
+        }
+    };
+
+    let status = results.iter().fold(Status::Unlocked, |status, result| {
+        status.merge(match result {
+            Ok(lock_info) => Status::of(lock_info),
+            Err(_) => Status::Error,
+        })
+    });
+
+    // process ที่ตรวจไม่ได้เป็นชุดเดียวกันทุกไฟล์ จึงเก็บไว้แสดงครั้งเดียว
+    let inaccessible = results
+        .iter()
+        .find_map(|result| result.as_ref().ok())
+        .map(|lock_info| lock_info.inaccessible.clone())
+        .unwrap_or_default();
+
+    let mut results: Vec<(&str, Result<FileLockInfo, FlcError>)> =
+        paths.iter().copied().zip(results).collect();
+    // path ที่ไม่มีใครใช้ใน tree มักมีเป็นพัน จึงแสดงเฉพาะที่ถูกใช้หรือตรวจไม่ได้
+    if roots.is_some() {
+        results.retain(|(_, result)| {
+            result
+                .as_ref()
+                .map_or(true, |lock_info| !lock_info.processes.is_empty())
+        });
+    }
+
+    // JSON รายงาน error ของแต่ละไฟล์ใน document เอง ส่วนรูปแบบอื่นแสดงทาง stderr
+    match format {
+        OutputFormat::Json if options.template.is_none() && options.compat.is_none() => {
+            let files = results
+                .iter()
+                .map(|(file_path, result)| match result {
+                    Ok(lock_info) => FileResult::Checked(lock_info),
+                    Err(error) => FileResult::Failed { file_path, error },
+                })
+                .collect();
+            print_json(Report::CheckMany { files });
+            return status;
+        }
+        OutputFormat::Ndjson if options.template.is_none() && options.compat.is_none() => {
+            for (file_path, result) in &results {
+                match result {
+                    Ok(lock_info) => print_json_line(Report::Check(lock_info)),
+                    Err(error) => print_json_line(Report::Error {
+                        file_path: Some(file_path),
+                        error,
+                    }),
+                }
+            }
+            return status;
+        }
+        _ => {}
+    }
+
+    let mut lock_infos = Vec::new();
+    for (file_path, result) in results {
+        match result {
+            Ok(lock_info) => lock_infos.push(lock_info),
+            Err(e) => eprintln!("Error checking '{}': {}", file_path, e),
+        }
+    }
+
+    if let Some(template) = &options.template {
+        for lock_info in &lock_infos {
+            print!("{}", template.render(lock_info));
+        }
+        return status;
+    }
+    if let Some(mode) = options.compat {
+        print!("{}", compat::render(mode, &lock_infos));
+        return status;
+    }
+
+    match format {
+        OutputFormat::Text => match roots {
+            Some(roots) => print_by_process(roots, &lock_infos, paths.len(), options.tree),
+            None => print_many(&lock_infos, paths.len()),
+        },
+        OutputFormat::Dot => print!("{}", render_dot(&lock_infos)),
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let separator = if format == OutputFormat::Csv {
+                ','
+            } else {
+                '\t'
+            };
+            print!(
+                "{}",
+                render_delimited(&lock_infos, &options.columns, separator)
+            );
         }
+        OutputFormat::Json | OutputFormat::Ndjson => unreachable!(),
+    }
+
+    if format == OutputFormat::Text {
+        if verbosity() > Verbosity::Quiet {
+            print_inaccessible(&inaccessible);
+        }
+        pause(options);
+    }
+    status
+}
+
+// แสดงผลแบบ text ของหลายไฟล์: เฉพาะไฟล์ที่ถูกใช้อยู่ (verbose แสดงทุกไฟล์) แล้วสรุปท้าย
+fn print_many(lock_infos: &[FileLockInfo], total: usize) {
+    let mut locked = 0;
+    for lock_info in lock_infos {
+        if !lock_info.processes.is_empty() {
+            locked += 1;
+            print_holders(lock_info);
+        } else if verbosity() == Verbosity::Verbose {
+            println!(
+                "File '{}' is not locked by any process",
+                lock_info.file_path
+            );
+        }
+    }
+
+    println!(
+        "Checked {} file(s): {} locked, {} not locked, {} failed",
+        total,
+        locked,
+        lock_infos.len() - locked,
+        total - lock_infos.len()
+    );
+}
+
+// แสดงผลของ check --recursive จัดกลุ่มตาม process พร้อม path ที่แต่ละ process ใช้
+// lock_infos มีเฉพาะ path ที่ถูกใช้ ส่วน total คือจำนวน path ที่ตรวจทั้งหมด
+fn print_by_process(roots: &[String], lock_infos: &[FileLockInfo], total: usize, tree: bool) {
+    // process เดียวกันที่ใช้หลาย path รวมเป็นกลุ่มเดียว เรียงตาม PID
+    let mut groups: BTreeMap<u32, Vec<(&str, &ProcessInfo)>> = BTreeMap::new();
+    for lock_info in lock_infos {
+        for process in &lock_info.processes {
+            groups
+                .entry(process.pid)
+                .or_default()
+                .push((&lock_info.file_path, process));
+        }
+    }
+
+    let roots_label = roots
+        .iter()
+        .map(|root| format!("'{}'", root))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if groups.is_empty() {
+        println!("Nothing under {} is in use", roots_label);
+    } else {
+        println!(
+            "{} path(s) under {} in use by {} process(es):",
+            lock_infos.len(),
+            roots_label,
+            groups.len()
+        );
+        println!("{:-<80}", "");
+    }
+
+    for (i, uses) in groups.values().enumerate() {
+        let process = uses[0].1;
+        println!("Process #{}", i + 1);
+        println!("  PID: {}", process.pid);
+        println!("  Name: {}", process.name);
+        println!("  Path: {}", process.path);
+        if let Some(user) = &process.user {
+            println!("  User: {}", user);
+        }
+        println!("  Files:");
+        for (file_path, usage) in uses {
+            // backend ที่ไม่รู้ว่าใช้ผ่านอะไร (Restart Manager) แสดงแค่ path
+            if usage.handles.is_empty() {
+                println!("    {}", file_path);
+            }
+            for handle in &usage.handles {
+                println!("    {}", handle);
+            }
+            for lock in &usage.locks {
+                println!("      {}", lock);
+            }
+        }
+        println!();
+    }
+
+    if tree && !groups.is_empty() {
+        for root in roots {
+            print!("{}", render_tree(root, lock_infos));
+        }
+        println!();
+    }
+
+    if verbosity() > Verbosity::Quiet {
+        println!("Scanned {} path(s)", total);
+    }
+}
+
+// รอกด enter หลังแสดงผลแบบ text เพื่อให้หน้าต่างที่เปิดจาก context menu ไม่ปิดทันที
+// ไม่มีใครกด enter ให้เมื่อ stdin ไม่ใช่ terminal เช่นใน CI
+fn pause(options: &CheckOptions) {
+    if options.pause && io::stdin().is_terminal() {
+        print!("Press enter to continue...");
+        io::stdout().flush().unwrap();
+        let mut _wait = String::new();
+        let _ = io::stdin().read_line(&mut _wait);
     }
-    print!("Press enter to continue...");
-    io::stdout().flush().unwrap();
-    let mut _wait = String::new();
-    let _ = io::stdin().read_line(&mut _wait);
 }
 
 // คำสั่ง unlock ไฟล์โดยการ kill processes
-pub fn unlock(file_path: &str) {
+// assume_yes คือ --yes: kill โดยไม่ถาม
+pub fn unlock(file_path: &str, format: OutputFormat, assume_yes: bool) -> Status {
+    if format == OutputFormat::Json {
+        return unlock_json(file_path, assume_yes);
+    }
+
     match check_file_locks(file_path) {
         Ok(lock_info) => {
             if lock_info.processes.is_empty() {
                 println!("File '{}' is not locked by any process", file_path);
-                return;
+                return Status::of(&lock_info);
             }
 
             print_lock_info(&lock_info);
             println!();
 
-            print!("Do you want to kill these processes? (y/N): ");
-            io::stdout().flush().unwrap();
-
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
-
-            if input.trim().to_lowercase() == "y" || input.trim().to_lowercase() == "yes" {
-                kill_processes(&lock_info.processes);
+            if confirm_kill(assume_yes, false) {
+                for outcome in kill_processes(&lock_info.processes) {
+                    print_kill_outcome(&outcome);
+                }
 
                 // ตรวจสอบอีกครั้งหลังจาก kill processes
                 println!("\nRechecking file locks...");
@@ -57,65 +512,831 @@ pub fn unlock(file_path: &str) {
                             println!("Warning: Some processes are still locking the file:");
                             print_lock_info(&new_lock_info);
                         }
+                        Status::of(&new_lock_info)
                     }
                     Err(e) => {
                         eprintln!("Error rechecking file locks: {}", e);
+                        Status::Error
                     }
                 }
             } else {
                 println!("Operation cancelled");
+                Status::Locked
             }
         }
         Err(e) => {
             eprintln!("Error checking file locks: {}", e);
+            Status::Error
+        }
+    }
+}
+
+// unlock หลายไฟล์จาก pattern: แสดง process ของทุกไฟล์ที่ถูกใช้แล้วถามครั้งเดียว
+pub fn unlock_many(file_paths: &[String], assume_yes: bool) -> Status {
+    let paths: Vec<&str> = file_paths.iter().map(String::as_str).collect();
+    let (locked, status) = match locked_files(&paths) {
+        Some(checked) => checked,
+        None => return Status::Error,
+    };
+    if locked.is_empty() {
+        println!(
+            "None of the {} file(s) is locked by any process",
+            paths.len()
+        );
+        return status;
+    }
+
+    for lock_info in &locked {
+        print_holders(lock_info);
+    }
+    println!();
+
+    if !confirm_kill(assume_yes, false) {
+        println!("Operation cancelled");
+        return Status::Locked;
+    }
+
+    // process เดียวกันมักถือหลายไฟล์ จึง kill ครั้งเดียวต่อ PID
+    let mut processes: Vec<ProcessInfo> = Vec::new();
+    for process in locked.iter().flat_map(|lock_info| &lock_info.processes) {
+        if !processes.iter().any(|p| p.pid == process.pid) {
+            processes.push(process.clone());
+        }
+    }
+    for outcome in kill_processes(&processes) {
+        print_kill_outcome(&outcome);
+    }
+
+    println!("\nRechecking file locks...");
+    thread::sleep(Duration::from_millis(500));
+
+    let paths: Vec<&str> = locked
+        .iter()
+        .map(|lock_info| lock_info.file_path.as_str())
+        .collect();
+    let (still_locked, status) = match locked_files(&paths) {
+        Some(checked) => checked,
+        None => return Status::Error,
+    };
+    if still_locked.is_empty() {
+        println!("All {} file(s) are now unlocked", paths.len());
+    } else {
+        println!("Warning: Some processes are still locking the files:");
+        for lock_info in &still_locked {
+            print_holders(lock_info);
         }
     }
+    status
+}
+
+// ตรวจหลายไฟล์แล้วคืนเฉพาะไฟล์ที่ถูกใช้ พร้อม Status รวม ไฟล์ที่ตรวจไม่ได้แจ้งทาง stderr
+// คืน None เมื่อสแกนไม่ได้ทั้งหมด
+fn locked_files(paths: &[&str]) -> Option<(Vec<FileLockInfo>, Status)> {
+    let results = match check_many_file_locks(paths) {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("Error checking file locks: {}", e);
+            return None;
+        }
+    };
+
+    let mut locked = Vec::new();
+    let mut status = Status::Unlocked;
+    for (file_path, result) in paths.iter().zip(results) {
+        match result {
+            Ok(lock_info) => {
+                status = status.merge(Status::of(&lock_info));
+                if !lock_info.processes.is_empty() {
+                    locked.push(lock_info);
+                }
+            }
+            Err(e) => {
+                eprintln!("Error checking '{}': {}", file_path, e);
+                status = status.merge(Status::Error);
+            }
+        }
+    }
+    Some((locked, status))
+}
+
+// unlock แบบ JSON: แสดง process ทาง stderr ก่อนถาม เพื่อให้ stdout มีแค่ document เดียว
+fn unlock_json(file_path: &str, assume_yes: bool) -> Status {
+    let before = match check_file_locks(file_path) {
+        Ok(lock_info) => lock_info,
+        Err(e) => {
+            print_json_error(file_path, &e);
+            return Status::Error;
+        }
+    };
+
+    let mut report = UnlockReport {
+        file_path: file_path.to_string(),
+        cancelled: true,
+        killed: Vec::new(),
+        after: None,
+        before,
+    };
+
+    if !report.before.processes.is_empty() {
+        eprintln!(
+            "File '{}' is locked by {} process(es):",
+            file_path,
+            report.before.processes.len()
+        );
+        for process in &report.before.processes {
+            eprintln!("  PID: {} | Name: {}", process.pid, process.name);
+        }
+
+        if confirm_kill(assume_yes, true) {
+            report.cancelled = false;
+            report.killed = kill_processes(&report.before.processes);
+            thread::sleep(Duration::from_millis(500));
+            match check_file_locks(file_path) {
+                Ok(lock_info) => report.after = Some(lock_info),
+                Err(e) => {
+                    print_json_error(file_path, &e);
+                    return Status::Error;
+                }
+            }
+        }
+    }
+
+    print_json(Report::Unlock(&report));
+    Status::of(report.after.as_ref().unwrap_or(&report.before))
+}
+
+// ถามก่อน kill เว้นแต่ใช้ --yes และจะไม่ kill เมื่อ stdin ไม่ใช่ terminal เพราะไม่มีใครตอบได้
+// to_stderr ใช้กับ JSON เพื่อให้ stdout มีแค่ document
+fn confirm_kill(assume_yes: bool, to_stderr: bool) -> bool {
+    if assume_yes {
+        return true;
+    }
+    if !io::stdin().is_terminal() {
+        eprintln!("stdin is not a terminal, not killing without --yes");
+        return false;
+    }
+
+    let question = "Do you want to kill these processes? (y/N): ";
+    if to_stderr {
+        eprint!("{}", question);
+    } else {
+        print!("{}", question);
+        io::stdout().flush().unwrap();
+    }
+    confirmed()
+}
+
+// อ่านคำตอบ y/N จาก stdin
+fn confirmed() -> bool {
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    let input = input.trim().to_lowercase();
+    input == "y" || input == "yes"
 }
 
 // คำสั่ง monitor ไฟล์แบบ real-time
-pub fn monitor(file_path: &str, interval_seconds: u64) {
-    println!("Monitoring file locks for: {}", file_path);
-    println!("Update interval: {} seconds", interval_seconds);
-    println!("Press Ctrl+C to stop monitoring\n");
+// pattern อาจได้หลายไฟล์ จึงเก็บ process ล่าสุดแยกตามไฟล์ และตรวจทุกไฟล์ในการสแกนครั้งเดียว
+pub fn monitor(file_paths: &[String], interval_seconds: u64, format: OutputFormat) {
+    let paths: Vec<&str> = file_paths.iter().map(String::as_str).collect();
+    match format {
+        OutputFormat::Json => return monitor_json(&paths, interval_seconds),
+        OutputFormat::Ndjson => return monitor_ndjson(&paths, interval_seconds),
+        _ => {}
+    }
+
+    if verbosity() > Verbosity::Quiet {
+        if let [file_path] = paths[..] {
+            println!("Monitoring file locks for: {}", file_path);
+        } else {
+            println!("Monitoring file locks for {} files:", paths.len());
+            for file_path in &paths {
+                println!("  {}", file_path);
+            }
+        }
+        println!("Update interval: {} seconds", interval_seconds);
+        println!("Press Ctrl+C to stop monitoring\n");
+    }
 
-    let mut last_processes: Vec<ProcessInfo> = Vec::new();
+    let mut last_processes: Vec<Vec<ProcessInfo>> = vec![Vec::new(); paths.len()];
 
     loop {
-        match check_file_locks(file_path) {
-            Ok(lock_info) => {
-                // ตรวจสอบว่ามีการเปลี่ยนแปลงหรือไม่
-                if processes_changed(&last_processes, &lock_info.processes) {
-                    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
-                    println!("[{}] Lock status changed:", timestamp);
+        for (i, result) in monitor_scan(&paths).into_iter().enumerate() {
+            match result {
+                Ok(lock_info) => {
+                    // ตรวจสอบว่ามีการเปลี่ยนแปลงหรือไม่
+                    if processes_changed(&last_processes[i], &lock_info.processes) {
+                        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+                        println!("[{}] Lock status changed:", timestamp);
 
-                    if lock_info.processes.is_empty() {
-                        println!("  File is now UNLOCKED");
-                    } else {
-                        println!(
-                            "  File is LOCKED by {} process(es):",
-                            lock_info.processes.len()
-                        );
-                        for process in &lock_info.processes {
+                        // ไฟล์เดียวแสดงแบบเดิม หลายไฟล์ต้องบอกว่าไฟล์ไหน
+                        let file = if paths.len() == 1 {
+                            "File".to_string()
+                        } else {
+                            format!("File '{}'", paths[i])
+                        };
+                        if lock_info.processes.is_empty() {
+                            println!("  {} is now UNLOCKED", file);
+                        } else {
                             println!(
-                                "    PID: {} | Name: {} | Path: {}",
-                                process.pid, process.name, process.path
+                                "  {} is LOCKED by {} process(es):",
+                                file,
+                                lock_info.processes.len()
                             );
+                            for process in &lock_info.processes {
+                                println!(
+                                    "    PID: {} | Name: {} | Path: {}",
+                                    process.pid, process.name, process.path
+                                );
+                            }
                         }
+                        println!();
+
+                        last_processes[i] = lock_info.processes;
+                    }
+                }
+                Err(e) => {
+                    if paths.len() == 1 {
+                        eprintln!("Error checking file locks: {}", e);
+                    } else {
+                        eprintln!("Error checking '{}': {}", paths[i], e);
+                    }
+                }
+            }
+        }
+
+        thread::sleep(Duration::from_secs(interval_seconds));
+    }
+}
+
+// ตรวจทุกไฟล์ของ monitor โดยได้ผลหนึ่งรายการต่อไฟล์เสมอ
+// ถ้าสแกนรวมไม่ได้ จะตรวจทีละไฟล์เพื่อให้แต่ละไฟล์ได้ error ของตัวเอง
+fn monitor_scan(paths: &[&str]) -> Vec<Result<FileLockInfo, FlcError>> {
+    check_many_file_locks(paths)
+        .unwrap_or_else(|_| paths.iter().map(|path| check_file_locks(path)).collect())
+}
+
+// monitor แบบ JSON: หนึ่ง document ต่อบรรทัดทุกครั้งที่สถานะเปลี่ยน
+fn monitor_json(paths: &[&str], interval_seconds: u64) {
+    let mut last_processes: Vec<Vec<ProcessInfo>> = vec![Vec::new(); paths.len()];
+    // ส่ง document แรกของทุกไฟล์เสมอ เพื่อให้รู้สถานะเริ่มต้นแม้ไฟล์จะไม่ถูก lock
+    let mut first = vec![true; paths.len()];
+
+    loop {
+        for (i, result) in monitor_scan(paths).into_iter().enumerate() {
+            match result {
+                Ok(lock_info) => {
+                    if first[i] || processes_changed(&last_processes[i], &lock_info.processes) {
+                        let change = MonitorChange::new(paths[i], &lock_info.processes);
+                        print_json_line(Report::MonitorChange(&change));
+                        last_processes[i] = lock_info.processes;
+                        first[i] = false;
                     }
-                    println!();
+                }
+                Err(e) => {
+                    print_json_line(Report::Error {
+                        file_path: Some(paths[i]),
+                        error: &e,
+                    });
+                }
+            }
+        }
 
-                    last_processes = lock_info.processes;
+        thread::sleep(Duration::from_secs(interval_seconds));
+    }
+}
+
+// monitor แบบ NDJSON: หนึ่งบรรทัดต่อ event locked, unlocked, holder_added และ holder_removed
+fn monitor_ndjson(paths: &[&str], interval_seconds: u64) {
+    let mut last_processes: Vec<Vec<ProcessInfo>> = vec![Vec::new(); paths.len()];
+
+    loop {
+        for (i, result) in monitor_scan(paths).into_iter().enumerate() {
+            match result {
+                Ok(lock_info) => {
+                    for event in monitor_events(paths[i], &last_processes[i], &lock_info.processes)
+                    {
+                        print_json_line(Report::MonitorEvent(&event));
+                    }
+                    last_processes[i] = lock_info.processes;
                 }
+                Err(e) => {
+                    print_json_line(Report::Error {
+                        file_path: Some(paths[i]),
+                        error: &e,
+                    });
+                }
+            }
+        }
+
+        thread::sleep(Duration::from_secs(interval_seconds));
+    }
+}
+
+// คำสั่งรอจนไม่มี process ถือไฟล์ หรือจนหมดเวลา timeout (วินาที)
+// หมดเวลาแล้วยังถูก lock จะแสดง holder ที่เหลือและคืน Status::Locked ไม่เช่นนั้นคืน Status::Unlocked
+pub fn wait_unlocked(
+    file_path: &str,
+    timeout_seconds: Option<u64>,
+    interval_seconds: u64,
+    format: OutputFormat,
+) -> Status {
+    if format == OutputFormat::Text && verbosity() > Verbosity::Quiet {
+        println!("Waiting for '{}' to be unlocked...", file_path);
+    }
+
+    let deadline = timeout_seconds.map(|seconds| Instant::now() + Duration::from_secs(seconds));
+    let lock_info = match wait_until_free(
+        &[file_path.to_string()],
+        deadline,
+        Duration::from_secs(interval_seconds),
+    ) {
+        Ok(mut lock_infos) => lock_infos.remove(0),
+        Err(e) => {
+            if format == OutputFormat::Json {
+                print_json_error(file_path, &e);
+            } else {
+                eprintln!("Error checking file locks: {}", e);
             }
+            return Status::Error;
+        }
+    };
+
+    if format == OutputFormat::Json {
+        print_json(Report::Check(&lock_info));
+    } else if lock_info.processes.is_empty() {
+        println!("File '{}' is unlocked", file_path);
+        if verbosity() > Verbosity::Quiet {
+            print_inaccessible(&lock_info.inaccessible);
+        }
+    } else {
+        eprintln!(
+            "Timed out after {} seconds, file is still locked",
+            timeout_seconds.unwrap_or_default()
+        );
+        print_lock_info(&lock_info);
+    }
+    Status::after_wait(&lock_info)
+}
+
+// รอจนทุกไฟล์ไม่มี holder หรือจนถึง deadline แล้วคืนผลการตรวจครั้งสุดท้ายของแต่ละไฟล์
+// แต่ละรอบตรวจทุกไฟล์ด้วยการสแกนครั้งเดียว
+fn wait_until_free(
+    file_paths: &[String],
+    deadline: Option<Instant>,
+    interval: Duration,
+) -> Result<Vec<FileLockInfo>, FlcError> {
+    let paths: Vec<&str> = file_paths.iter().map(String::as_str).collect();
+    loop {
+        let lock_infos = check_many_file_locks(&paths)?
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let now = Instant::now();
+        let free = lock_infos
+            .iter()
+            .all(|lock_info| lock_info.processes.is_empty());
+        if free || deadline.is_some_and(|deadline| now >= deadline) {
+            return Ok(lock_infos);
+        }
+
+        // ไม่รอเกินเวลาที่เหลือ เพื่อให้ตรวจครั้งสุดท้ายตรงกับ timeout
+        let wait = match deadline {
+            Some(deadline) => interval.min(deadline - now),
+            None => interval,
+        };
+        thread::sleep(wait);
+    }
+}
+
+// exit code เมื่อ exec หมดเวลารอโดยไม่ได้รันคำสั่ง เหมือนกับ timeout(1)
+pub const EXEC_TIMED_OUT: u8 = 124;
+
+// ตัวเลือกของคำสั่ง exec
+pub struct ExecOptions {
+    // ไฟล์ที่ต้องว่างก่อนรันคำสั่ง
+    pub file_paths: Vec<String>,
+    // จำนวนครั้งที่รันซ้ำเมื่อคำสั่งล้มเหลวเพราะมี holder
+    pub retries: u32,
+    // วินาทีที่รอก่อนรันซ้ำครั้งแรก แล้วเพิ่มเป็นสองเท่าทุกครั้ง
+    pub backoff_seconds: u64,
+    // เวลารอสูงสุดให้ไฟล์ว่างในแต่ละรอบ
+    pub timeout_seconds: Option<u64>,
+    pub interval_seconds: u64,
+}
+
+// คำสั่งรอให้ไฟล์ว่างแล้วรันคำสั่ง ถ้าคำสั่งล้มเหลวและมี process มาถือไฟล์จะรอแล้วรันใหม่
+// ข้อความของ exec ออกทาง stderr เพื่อไม่ปนกับ output ของคำสั่ง
+// exit code เป็นของคำสั่งที่รันครั้งสุดท้าย เว้นแต่หมดเวลารอ (EXEC_TIMED_OUT) หรือรันคำสั่งไม่ได้ (Status::Error)
+pub fn exec(command: &[String], options: &ExecOptions) -> ExitCode {
+    let interval = Duration::from_secs(options.interval_seconds);
+    let mut backoff = Duration::from_secs(options.backoff_seconds);
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let deadline = options
+            .timeout_seconds
+            .map(|seconds| Instant::now() + Duration::from_secs(seconds));
+        let lock_infos = match wait_until_free(&options.file_paths, deadline, interval) {
+            Ok(lock_infos) => lock_infos,
             Err(e) => {
                 eprintln!("Error checking file locks: {}", e);
+                return Status::Error.into();
+            }
+        };
+        if lock_infos
+            .iter()
+            .any(|lock_info| !lock_info.processes.is_empty())
+        {
+            eprintln!(
+                "Timed out after {} seconds, not running '{}'",
+                options.timeout_seconds.unwrap_or_default(),
+                command[0]
+            );
+            for lock_info in &lock_infos {
+                eprint_holders(lock_info);
             }
+            return ExitCode::from(EXEC_TIMED_OUT);
         }
 
-        thread::sleep(Duration::from_secs(interval_seconds));
+        let status = match std::process::Command::new(&command[0])
+            .args(&command[1..])
+            .status()
+        {
+            Ok(status) => status,
+            Err(e) => {
+                eprintln!("Failed to run '{}': {}", command[0], e);
+                return Status::Error.into();
+            }
+        };
+        if status.success() {
+            return ExitCode::SUCCESS;
+        }
+        let code = child_exit_code(status);
+
+        // ตรวจหลังล้มเหลวว่ามีใครมาถือไฟล์ ถ้าไม่มีแสดงว่าล้มเหลวด้วยเหตุอื่น จึงไม่รันซ้ำ
+        let paths: Vec<&str> = options.file_paths.iter().map(String::as_str).collect();
+        let holders: Vec<FileLockInfo> = check_many_file_locks(&paths)
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter(|lock_info| !lock_info.processes.is_empty())
+            .collect();
+        if holders.is_empty() {
+            eprintln!(
+                "'{}' failed ({}) and no process holds the files, not retrying",
+                command[0], status
+            );
+            return ExitCode::from(code);
+        }
+
+        eprintln!(
+            "Attempt {} of {} failed ({}), in the way:",
+            attempt,
+            options.retries + 1,
+            status
+        );
+        for lock_info in &holders {
+            eprint_holders(lock_info);
+        }
+        if attempt > options.retries {
+            eprintln!("Giving up after {} attempt(s)", attempt);
+            return ExitCode::from(code);
+        }
+
+        eprintln!("Retrying in {} seconds", backoff.as_secs());
+        thread::sleep(backoff);
+        backoff *= 2;
+    }
+}
+
+// exit code ของคำสั่งลูกในช่วง 0-255 ที่ ExitCode รองรับ
+// ถูก signal ฆ่าได้ 128 + เลข signal แบบ shell ส่วน code ที่เกิน 255 (เช่น NTSTATUS บน Windows) ได้ 255
+fn child_exit_code(status: std::process::ExitStatus) -> u8 {
+    #[cfg(unix)]
+    if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+        return 128u8.saturating_add(u8::try_from(signal).unwrap_or(u8::MAX));
+    }
+    status
+        .code()
+        .and_then(|code| u8::try_from(code).ok())
+        .unwrap_or(u8::MAX)
+}
+
+// แสดง holder ของไฟล์ทาง stderr บรรทัดละ process
+fn eprint_holders(lock_info: &FileLockInfo) {
+    for process in &lock_info.processes {
+        eprintln!(
+            "  {}: {} (PID: {})",
+            lock_info.file_path, process.name, process.pid
+        );
+    }
+}
+
+// คำสั่งแสดง byte-range locks ของไฟล์
+pub fn ranges(file_path: &str) -> Status {
+    let file_size = fs::metadata(file_path).map(|m| m.len()).unwrap_or(0);
+
+    match check_file_locks(file_path) {
+        Ok(lock_info) => {
+            print_range_map(&lock_info, file_size);
+            Status::of(&lock_info)
+        }
+        Err(e) => {
+            eprintln!("Error checking file locks: {}", e);
+            Status::Error
+        }
     }
 }
 
+// คำสั่งทดสอบว่าคำขอ lock จะได้หรือไม่ และถูกใคร block
+pub fn probe(file_path: &str, request: &LockRequest) -> Status {
+    let end = if request.len == 0 {
+        "EOF".to_string()
+    } else {
+        request.start.saturating_add(request.len - 1).to_string()
+    };
+    let description = format!(
+        "{} {} lock on bytes {}-{}",
+        request.class, request.mode, request.start, end
+    );
+
+    match probe_lock(file_path, request) {
+        Ok(None) => {
+            println!("{} of '{}' would be granted", description, file_path);
+            Status::Unlocked
+        }
+        Ok(Some(conflict)) => {
+            println!("{} of '{}' would block", description, file_path);
+            match conflict.pid {
+                Some(pid) => println!(
+                    "  Holder: {} (PID: {})",
+                    conflict.name.as_deref().unwrap_or("unknown"),
+                    pid
+                ),
+                None => println!("  Holder: unknown (OFD locks are not owned by a process)"),
+            }
+            println!("  Lock: {}", conflict.record);
+            Status::Locked
+        }
+        Err(e) => {
+            eprintln!("Error probing lock: {}", e);
+            Status::Error
+        }
+    }
+}
+
+// คำสั่งอธิบายว่าทำไมจึงแก้ไขหรือลบไฟล์ไม่ได้
+pub fn explain(file_path: &str) -> Status {
+    let explanation = match explain_file(file_path) {
+        Ok(explanation) => explanation,
+        Err(e) => {
+            eprintln!("Error explaining file access: {}", e);
+            return Status::Error;
+        }
+    };
+
+    println!("Access to '{}':", file_path);
+    for probe in &explanation.probes {
+        match &probe.outcome {
+            Ok(()) => println!("  {:<7} allowed", probe.mode),
+            Err(denial) => println!("  {:<7} denied ({})", probe.mode, denial),
+        }
+    }
+    println!();
+
+    if explanation.findings.is_empty() {
+        println!("No reason found that would prevent writing or deleting the file");
+    } else {
+        println!("Found {} reason(s):", explanation.findings.len());
+        println!("{:-<80}", "");
+        for finding in &explanation.findings {
+            println!("  [{}] {}", finding.reason, finding.detail);
+        }
+    }
+    println!();
+    if verbosity() > Verbosity::Quiet {
+        println!("Checked: {}", explanation.checked.join(", "));
+    }
+
+    if explanation.findings.is_empty() {
+        Status::Unlocked
+    } else {
+        Status::Locked
+    }
+}
+
+// คำสั่งตรวจหา deadlock จาก process ที่รอ lock กันเป็นวง
+// exit code เป็น Locked เมื่อพบ deadlock
+pub fn deadlock() -> Status {
+    let waits = match lock_waits() {
+        Ok(waits) => waits,
+        Err(e) => {
+            eprintln!("Error reading lock waits: {}", e);
+            return Status::Error;
+        }
+    };
+
+    if waits.is_empty() {
+        println!("No processes are waiting on file locks");
+        return Status::Unlocked;
+    }
+
+    let cycles = find_cycles(&waits);
+    if cycles.is_empty() {
+        println!(
+            "{} process(es) waiting on file locks, no deadlock found",
+            waits.len()
+        );
+        return Status::Unlocked;
+    }
+
+    println!("Found {} deadlock(s):", cycles.len());
+    println!("{:-<80}", "");
+    for (i, cycle) in cycles.iter().enumerate() {
+        let pids: Vec<String> = cycle
+            .iter()
+            .map(|wait| wait.waiter_pid.to_string())
+            .chain(std::iter::once(cycle[0].waiter_pid.to_string()))
+            .collect();
+        println!("Deadlock #{}: {}", i + 1, pids.join(" -> "));
+
+        for wait in cycle {
+            println!(
+                "  {} (PID: {}) waits for {} (PID: {})",
+                wait.waiter_name, wait.waiter_pid, wait.holder_name, wait.holder_pid
+            );
+            println!("    File: {}", wait.file_path);
+            println!("    Requested: {}", wait.requested);
+            println!("    Held: {}", wait.held);
+        }
+        println!();
+    }
+    Status::Locked
+}
+
+// คำสั่งแสดงไฟล์ที่ถูกลบแล้วแต่ยังมี process เปิดอยู่ เรียงตามพื้นที่ที่ใช้
+// พบไฟล์จะคืน Status::Locked เพื่อให้ script รู้ว่ามีพื้นที่ที่ยังไม่ถูกคืน
+pub fn deleted(format: OutputFormat) -> Status {
+    let deleted = match deleted_files() {
+        Ok(deleted) => deleted,
+        Err(e) => {
+            if format == OutputFormat::Json {
+                print_json(Report::Error {
+                    file_path: None,
+                    error: &e,
+                });
+            } else {
+                eprintln!("Error finding deleted files: {}", e);
+            }
+            return Status::Error;
+        }
+    };
+
+    let status = if !deleted.files.is_empty() {
+        Status::Locked
+    } else if !deleted.inaccessible.is_empty() {
+        Status::Partial
+    } else {
+        Status::Unlocked
+    };
+
+    if format == OutputFormat::Json {
+        print_json(Report::Deleted(&deleted));
+        return status;
+    }
+
+    print_deleted(&deleted);
+    if verbosity() > Verbosity::Quiet {
+        print_inaccessible(&deleted.inaccessible);
+    }
+    status
+}
+
+// แสดงไฟล์ที่ถูกลบทีละไฟล์ พร้อม filesystem และ process ที่ยังเปิดไว้
+fn print_deleted(deleted: &DeletedFiles) {
+    if deleted.files.is_empty() {
+        println!("No deleted files are held open");
+        return;
+    }
+
+    let total: u64 = deleted.files.iter().map(|file| file.allocated).sum();
+    println!(
+        "{} deleted file(s) still held open, using {}:",
+        deleted.files.len(),
+        format_size(total)
+    );
+    println!("{:-<80}", "");
+    for file in &deleted.files {
+        // ไฟล์ sparse ใช้พื้นที่น้อยกว่าขนาด จึงแสดงขนาดด้วยเมื่อไม่เท่ากัน
+        if file.size > file.allocated {
+            println!(
+                "{:>10}  {}  (size {})",
+                format_size(file.allocated),
+                file.path,
+                format_size(file.size)
+            );
+        } else {
+            println!("{:>10}  {}", format_size(file.allocated), file.path);
+        }
+        match &file.filesystem {
+            Some(fs) => println!(
+                "            Filesystem: {} ({} on {}), inode {}:{}",
+                fs.mount_point, fs.fs_type, fs.source, file.dev, file.ino
+            ),
+            None => println!(
+                "            Filesystem: unknown, inode {}:{}",
+                file.dev, file.ino
+            ),
+        }
+        for holder in &file.holders {
+            let fds: Vec<String> = holder.fds.iter().map(|fd| fd.to_string()).collect();
+            println!(
+                "            Held by: {} (PID: {}) fd {}",
+                holder.name,
+                holder.pid,
+                fds.join(", ")
+            );
+        }
+    }
+
+    if verbosity() > Verbosity::Quiet {
+        println!();
+        println!("Hint: the space is freed when these processes close the files or exit");
+    }
+}
+
+// แสดงจำนวน byte แบบอ่านง่าย เช่น "512 B" หรือ "1.5 GiB"
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+// kill processes ทั้งหมดที่ lock ไฟล์
+pub fn kill_processes(processes: &[ProcessInfo]) -> Vec<KillOutcome> {
+    processes
+        .iter()
+        .map(|process| KillOutcome {
+            pid: process.pid,
+            name: process.name.clone(),
+            error: kill_process(process.pid).err(),
+        })
+        .collect()
+}
+
+// แสดงผลการ kill process หนึ่งตัว
+fn print_kill_outcome(outcome: &KillOutcome) {
+    match &outcome.error {
+        None => {
+            println!(
+                "Successfully killed process: {} (PID: {})",
+                outcome.name, outcome.pid
+            );
+        }
+        Some(e) => {
+            eprintln!(
+                "Failed to kill process {} (PID: {}): {}",
+                outcome.name, outcome.pid, e
+            );
+        }
+    }
+}
+
+// แสดง document JSON แบบอ่านง่าย
+fn print_json(report: Report) {
+    match serde_json::to_string_pretty(&Document::new(report)) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Error serializing JSON: {}", e),
+    }
+}
+
+// แสดง document JSON ในบรรทัดเดียว สำหรับ output ที่ต่อเนื่อง
+fn print_json_line(report: Report) {
+    match serde_json::to_string(&Document::new(report)) {
+        Ok(json) => {
+            println!("{}", json);
+            let _ = io::stdout().flush();
+        }
+        Err(e) => eprintln!("Error serializing JSON: {}", e),
+    }
+}
+
+fn print_json_error(file_path: &str, error: &FlcError) {
+    print_json(Report::Error {
+        file_path: Some(file_path),
+        error,
+    });
+}
+
 // แสดงข้อมูล lock ของไฟล์
 fn print_lock_info(lock_info: &FileLockInfo) {
     if lock_info.processes.is_empty() {
@@ -124,6 +1345,11 @@ fn print_lock_info(lock_info: &FileLockInfo) {
             lock_info.file_path
         );
 
+        if verbosity() == Verbosity::Quiet {
+            return;
+        }
+        print_inaccessible(&lock_info.inaccessible);
+
         // ตรวจสอบเพิ่มเติมสำหรับไฟล์ .sys
         if lock_info.file_path.ends_with(".sys") {
             println!("Note: .sys files (kernel drivers) may be locked by kernel-level processes");
@@ -131,24 +1357,147 @@ fn print_lock_info(lock_info: &FileLockInfo) {
             println!("      Try using 'handle.exe' from Sysinternals for more detailed analysis.");
         }
 
-        // ทดสอบการเขียนไฟล์
-        if let Err(_) = test_file_access(&lock_info.file_path) {
-            println!("Warning: File appears to be locked despite no processes found.");
-            println!("         This may indicate kernel-level or system service locks.");
+        // verbose แสดงผลทุกแบบแทนคำเตือน
+        if verbosity() == Verbosity::Verbose {
+            print_access(&lock_info.file_path);
+            return;
+        }
+
+        // ทดสอบการเข้าถึงแต่ละแบบ เพื่อบอกว่าทำอะไรกับไฟล์ไม่ได้บ้างและเพราะอะไร
+        let denied: Vec<_> = probe_access(&lock_info.file_path)
+            .into_iter()
+            .filter_map(|probe| probe.outcome.err().map(|denial| (probe.mode, denial)))
+            // ไฟล์ทั่วไปที่ไม่มี x bit รันไม่ได้อยู่แล้ว ไม่นับว่าถูก lock
+            .filter(|(mode, denial)| {
+                !(*mode == AccessMode::Exec && denial.class == DenialClass::PermissionDenied)
+            })
+            .collect();
+        if !denied.is_empty() {
+            println!("Warning: File cannot be fully accessed despite no processes found:");
+            for (mode, denial) in &denied {
+                println!("  {:<7} {}", mode, denial);
+            }
+            if denied.iter().any(|(_, denial)| {
+                matches!(denial.class, DenialClass::Busy | DenialClass::WouldBlock)
+            }) {
+                println!("This may indicate kernel-level or system service locks.");
+            }
         }
     } else {
+        print_holders(lock_info);
+
+        if verbosity() > Verbosity::Quiet {
+            print_inaccessible(&lock_info.inaccessible);
+        }
+        if verbosity() == Verbosity::Verbose {
+            print_access(&lock_info.file_path);
+        }
+    }
+}
+
+// แสดง process ที่ถือ lock แยกจาก process ที่แค่เปิดไฟล์ไว้
+fn print_holders(lock_info: &FileLockInfo) {
+    let (holders, openers): (Vec<&ProcessInfo>, Vec<&ProcessInfo>) = lock_info
+        .processes
+        .iter()
+        .partition(|process| !process.locks.is_empty());
+
+    // backend ที่ไม่รายงานข้อมูล lock (เช่น Restart Manager) ให้แสดงแบบเดิม
+    if holders.is_empty() {
         println!(
             "File '{}' is locked by {} process(es):",
             lock_info.file_path,
-            lock_info.processes.len()
+            openers.len()
         );
         println!("{:-<80}", "");
-        for (i, process) in lock_info.processes.iter().enumerate() {
-            println!("Process #{}", i + 1);
-            println!("  PID: {}", process.pid);
-            println!("  Name: {}", process.name);
-            println!("  Path: {}", process.path);
-            println!();
+        print_processes(&openers);
+    } else {
+        println!(
+            "File '{}' is locked by {} process(es):",
+            lock_info.file_path,
+            holders.len()
+        );
+        println!("{:-<80}", "");
+        print_processes(&holders);
+
+        if !openers.is_empty() {
+            println!(
+                "Also opened by {} process(es) without holding a lock:",
+                openers.len()
+            );
+            println!("{:-<80}", "");
+            print_processes(&openers);
+        }
+    }
+}
+
+// บอกว่าผลอาจไม่ครบ เพราะมี process ที่อ่าน fd ไม่ได้ (verbose แสดง PID ด้วย)
+fn print_inaccessible(inaccessible: &[u32]) {
+    if inaccessible.is_empty() {
+        return;
+    }
+    println!(
+        "Note: {} process(es) could not be inspected (permission denied); run as root to include them",
+        inaccessible.len()
+    );
+    if verbosity() == Verbosity::Verbose {
+        let pids: Vec<String> = inaccessible.iter().map(|pid| pid.to_string()).collect();
+        println!("      PIDs: {}", pids.join(", "));
+    }
+}
+
+// แสดงผลการทดสอบการเข้าถึงทุกแบบ
+fn print_access(file_path: &str) {
+    println!("Access:");
+    for probe in probe_access(file_path) {
+        match &probe.outcome {
+            Ok(()) => println!("  {:<7} allowed", probe.mode),
+            Err(denial) => println!("  {:<7} denied ({})", probe.mode, denial),
+        }
+    }
+}
+
+// แสดงรายการ process พร้อม lock ที่ถืออยู่
+fn print_processes(processes: &[&ProcessInfo]) {
+    for (i, process) in processes.iter().enumerate() {
+        println!("Process #{}", i + 1);
+        println!("  PID: {}", process.pid);
+        println!("  Name: {}", process.name);
+        println!("  Path: {}", process.path);
+        if let Some(user) = &process.user {
+            println!("  User: {}", user);
+        }
+        if !process.handles.is_empty() {
+            println!("  Handles:");
+            for handle in &process.handles {
+                println!("    {}", handle);
+            }
+        }
+        if !process.locks.is_empty() {
+            println!("  Locks:");
+            for lock in &process.locks {
+                println!("    {}", lock);
+            }
         }
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use file_lock_checker::ProcessInfo;
+
+    #[test]
+    fn wait_succeeds_without_holders_even_if_some_processes_are_hidden() {
+        let mut lock_info = FileLockInfo {
+            inaccessible: vec![1, 2],
+            ..Default::default()
+        };
+        assert_eq!(Status::of(&lock_info), Status::Partial);
+        assert_eq!(Status::after_wait(&lock_info), Status::Unlocked);
+
+        lock_info.processes.push(ProcessInfo::default());
+        assert_eq!(Status::after_wait(&lock_info), Status::Locked);
     }
 }
@@ -0,0 +1,156 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use file_lock_checker::{FileLockInfo, HandleKind, LockMode, LockRecord, OpenHandle, ProcessInfo};
+
+// output ที่เลียนแบบเครื่องมืออื่น เพื่อให้ใช้แทนใน script เดิมได้
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Compat {
+    // lsof -F
+    Lsof,
+    // fuser -v
+    Fuser,
+}
+
+pub fn render(compat: Compat, lock_infos: &[FileLockInfo]) -> String {
+    match compat {
+        Compat::Lsof => lock_infos.iter().map(render_lsof).collect(),
+        Compat::Fuser => render_fuser(lock_infos),
+    }
+}
+
+// รูปแบบ field ของ lsof -F: หนึ่ง field ต่อบรรทัด ขึ้นต้นด้วยตัวอักษรระบุ field
+// process set: p (PID), c (command), L (login)
+// file set:    f (fd), a (access), l (lock), n (name)
+fn render_lsof(lock_info: &FileLockInfo) -> String {
+    let mut out = String::new();
+    for process in &lock_info.processes {
+        out.push_str(&format!("p{}\n", process.pid));
+        out.push_str(&format!("c{}\n", process.name));
+        if let Some(user) = &process.user {
+            out.push_str(&format!("L{}\n", user));
+        }
+
+        if process.handles.is_empty() {
+            // backend ไม่รู้ว่าเปิดผ่าน fd ไหน
+            out.push_str("fNOFD\n");
+            out.push_str(&format!("n{}\n", lock_info.file_path));
+            continue;
+        }
+        for handle in &process.handles {
+            out.push_str(&format!("f{}\n", lsof_fd(handle)));
+            if handle.kind == HandleKind::Fd {
+                let access = match (handle.read, handle.write) {
+                    (true, true) => "u",
+                    (false, true) => "w",
+                    (true, false) => "r",
+                    (false, false) => " ",
+                };
+                out.push_str(&format!("a{}\n", access));
+                out.push_str(&format!("l{}\n", lsof_lock(&handle.locks)));
+            }
+            out.push_str(&format!("n{}\n", handle.path));
+        }
+    }
+    out
+}
+
+// ชื่อ fd แบบ lsof: หมายเลข fd หรือ cwd, rtd, txt, mem
+fn lsof_fd(handle: &OpenHandle) -> String {
+    match handle.kind {
+        HandleKind::Fd => handle
+            .fd
+            .map(|fd| fd.to_string())
+            .unwrap_or_else(|| "NOFD".to_string()),
+        HandleKind::Cwd => "cwd".to_string(),
+        HandleKind::Root => "rtd".to_string(),
+        HandleKind::Exe => "txt".to_string(),
+        HandleKind::Mmap => "mem".to_string(),
+    }
+}
+
+// ตัวอักษร lock ของ lsof ต่อ fd: R/W ทั้งไฟล์, r/w บางส่วน, u ทั้ง read และ write,
+// ช่องว่างถ้า fd นี้ไม่ได้ถือ lock
+fn lsof_lock(locks: &[LockRecord]) -> char {
+    let has = |mode| locks.iter().any(|lock| lock.mode == mode);
+    if has(LockMode::Read) && has(LockMode::Write) {
+        return 'u';
+    }
+    // mode และขอบเขตต้องมาจาก lock เดียวกัน โดยเลือก lock ทั้งไฟล์ก่อนถ้ามี
+    let whole_file = |lock: &&LockRecord| lock.start == 0 && lock.end.is_none();
+    let Some(lock) = locks.iter().find(whole_file).or(locks.first()) else {
+        return ' ';
+    };
+    match (lock.mode, whole_file(&lock)) {
+        (LockMode::Read, true) => 'R',
+        (LockMode::Read, false) => 'r',
+        (LockMode::Write, true) => 'W',
+        (LockMode::Write, false) => 'w',
+    }
+}
+
+// รูปแบบตารางของ fuser -v โดย ACCESS มีห้าตำแหน่ง:
+// f/F (เปิดอยู่/เปิดเพื่อเขียน), r (root), c (cwd), e (executable), m (mmap)
+// header แสดงครั้งเดียวแม้มีหลายไฟล์ และไฟล์ที่ไม่มีใครใช้จะไม่แสดง
+fn render_fuser(lock_infos: &[FileLockInfo]) -> String {
+    let mut out = String::new();
+    for lock_info in lock_infos {
+        if lock_info.processes.is_empty() {
+            continue;
+        }
+        if out.is_empty() {
+            out.push_str(&format!("{:>20} USER        PID ACCESS COMMAND\n", ""));
+        }
+        render_fuser_file(lock_info, &mut out);
+    }
+    out
+}
+
+fn render_fuser_file(lock_info: &FileLockInfo, out: &mut String) {
+    let mut name = format!("{}:", lock_info.file_path);
+    // ชื่อที่ยาวเกินคอลัมน์ขึ้นบรรทัดของตัวเอง เหมือน fuser
+    if name.chars().count() > 20 {
+        out.push_str(&name);
+        out.push('\n');
+        name.clear();
+    }
+    for process in &lock_info.processes {
+        out.push_str(&format!(
+            "{:<21}{:<8} {:>6} {} {}\n",
+            name,
+            process.user.as_deref().unwrap_or("?"),
+            process.pid,
+            fuser_access(process),
+            process.name
+        ));
+        // ชื่อไฟล์แสดงเฉพาะบรรทัดแรก เหมือน fuser
+        name.clear();
+    }
+}
+
+fn fuser_access(process: &ProcessInfo) -> String {
+    let has = |kind| process.handles.iter().any(|handle| handle.kind == kind);
+    let file = if process
+        .handles
+        .iter()
+        .any(|handle| handle.kind == HandleKind::Fd && handle.write)
+    {
+        'F'
+    } else if has(HandleKind::Fd) || process.handles.is_empty() {
+        // ไม่มีข้อมูล handle (เช่น Restart Manager) ถือว่าเปิดไฟล์อยู่
+        'f'
+    } else {
+        '.'
+    };
+
+    let flag = |kind, letter| if has(kind) { letter } else { '.' };
+    [
+        file,
+        flag(HandleKind::Root, 'r'),
+        flag(HandleKind::Cwd, 'c'),
+        flag(HandleKind::Exe, 'e'),
+        flag(HandleKind::Mmap, 'm'),
+    ]
+    .iter()
+    .collect()
+}
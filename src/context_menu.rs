@@ -2,15 +2,22 @@
 // SPDX-License-Identifier: MIT
 
 use crate::registry::{create_registry_key, delete_registry_key};
+use file_lock_checker::FlcError;
 use std::env;
+use std::io;
 
 // ติดตั้ง context menu ทั้งหมด
-pub fn install() -> Result<(), String> {
+pub fn install() -> Result<(), FlcError> {
     // หา path ของ executable ปัจจุบัน
     let exe_path =
-        env::current_exe().map_err(|e| format!("Failed to get executable path: {}", e))?;
+        env::current_exe().map_err(|e| FlcError::io("Failed to get executable path", e))?;
 
-    let exe_path_str = exe_path.to_str().ok_or("Invalid executable path")?;
+    let exe_path_str = exe_path.to_str().ok_or_else(|| {
+        FlcError::io(
+            "Invalid executable path",
+            io::Error::new(io::ErrorKind::InvalidData, "path is not valid Unicode"),
+        )
+    })?;
 
     println!("Installing context menu for: {}", exe_path_str);
 
@@ -25,7 +32,7 @@ pub fn install() -> Result<(), String> {
 }
 
 // ติดตั้งสำหรับไฟล์ประเภทหนึ่ง
-fn install_for_file_type(file_type: &str, exe_path: &str) -> Result<(), String> {
+fn install_for_file_type(file_type: &str, exe_path: &str) -> Result<(), FlcError> {
     let base_key = format!("{}\\shell", file_type);
 
     // สร้าง menu items
@@ -37,18 +44,24 @@ fn install_for_file_type(file_type: &str, exe_path: &str) -> Result<(), String>
 }
 
 // ติดตั้งสำหรับ folders
-fn install_for_folders(exe_path: &str) -> Result<(), String> {
+fn install_for_folders(exe_path: &str) -> Result<(), FlcError> {
     let base_key = "Directory\\shell";
 
-    // สร้าง menu items สำหรับ folder
-    create_registry_key(base_key, "FLC_Check", "FLC Check", exe_path, "check")?;
+    // สร้าง menu items สำหรับ folder โดย check ตรวจทุกไฟล์ใน folder
+    create_registry_key(
+        base_key,
+        "FLC_Check",
+        "FLC Check",
+        exe_path,
+        "check --recursive --tree",
+    )?;
     create_registry_key(base_key, "FLC_Monitor", "FLC Monitor", exe_path, "monitor")?;
 
     Ok(())
 }
 
 // ถอนการติดตั้ง context menu
-pub fn uninstall() -> Result<(), String> {
+pub fn uninstall() -> Result<(), FlcError> {
     // ลบ menu items สำหรับ All Files
     delete_registry_key("*\\shell\\FLC_Check")?;
     delete_registry_key("*\\shell\\FLC_Unlock")?;
@@ -59,4 +72,4 @@ pub fn uninstall() -> Result<(), String> {
     delete_registry_key("Directory\\shell\\FLC_Monitor")?;
 
     Ok(())
-}
\ No newline at end of file
+}
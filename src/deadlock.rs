@@ -0,0 +1,106 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use crate::types::LockWait;
+use std::collections::{BTreeMap, HashSet};
+
+// หา cycle ใน wait-for graph (waiter -> holder)
+// แต่ละ cycle คือรายการ edge ที่เริ่มจาก PID ที่น้อยที่สุดใน cycle
+pub fn find_cycles(waits: &[LockWait]) -> Vec<Vec<&LockWait>> {
+    let mut graph: BTreeMap<u32, Vec<&LockWait>> = BTreeMap::new();
+    for wait in waits {
+        graph.entry(wait.waiter_pid).or_default().push(wait);
+    }
+
+    let mut cycles = Vec::new();
+    let mut seen: HashSet<Vec<u32>> = HashSet::new();
+
+    for &start in graph.keys() {
+        let mut path = Vec::new();
+        walk(&graph, start, start, &mut path, &mut cycles, &mut seen);
+    }
+
+    cycles
+}
+
+// DFS จาก start โดยเดินผ่านเฉพาะ PID ที่มากกว่า start เพื่อให้พบแต่ละ cycle ครั้งเดียว
+fn walk<'a>(
+    graph: &BTreeMap<u32, Vec<&'a LockWait>>,
+    start: u32,
+    current: u32,
+    path: &mut Vec<&'a LockWait>,
+    cycles: &mut Vec<Vec<&'a LockWait>>,
+    seen: &mut HashSet<Vec<u32>>,
+) {
+    let Some(edges) = graph.get(&current) else {
+        return;
+    };
+
+    for &edge in edges {
+        let next = edge.holder_pid;
+        path.push(edge);
+
+        if next == start {
+            // process อาจรอกันเองผ่านหลาย lock ให้นับเป็น cycle เดียว
+            let pids: Vec<u32> = path.iter().map(|wait| wait.waiter_pid).collect();
+            if seen.insert(pids) {
+                cycles.push(path.clone());
+            }
+        } else if next > start && !path.iter().any(|wait| wait.waiter_pid == next) {
+            walk(graph, start, next, path, cycles, seen);
+        }
+
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{LockClass, LockMode, LockRecord};
+
+    fn wait(waiter_pid: u32, holder_pid: u32) -> LockWait {
+        let record = LockRecord {
+            class: LockClass::Posix,
+            mode: LockMode::Write,
+            mandatory: false,
+            start: 0,
+            end: None,
+        };
+        LockWait {
+            file_path: format!("{}.db", holder_pid),
+            waiter_pid,
+            waiter_name: format!("p{}", waiter_pid),
+            requested: record.clone(),
+            holder_pid,
+            holder_name: format!("p{}", holder_pid),
+            held: record,
+        }
+    }
+
+    fn pids(cycle: &[&LockWait]) -> Vec<(u32, u32)> {
+        cycle
+            .iter()
+            .map(|wait| (wait.waiter_pid, wait.holder_pid))
+            .collect()
+    }
+
+    #[test]
+    fn chain_without_cycle_has_none() {
+        assert!(find_cycles(&[wait(1, 2), wait(2, 3)]).is_empty());
+    }
+
+    #[test]
+    fn cycle_starts_at_lowest_pid_and_is_found_once() {
+        let waits = [wait(30, 10), wait(10, 20), wait(20, 30), wait(40, 10)];
+        let cycles = find_cycles(&waits);
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(pids(&cycles[0]), [(10, 20), (20, 30), (30, 10)]);
+    }
+
+    #[test]
+    fn waiting_through_two_locks_counts_once() {
+        let waits = [wait(1, 2), wait(1, 2), wait(2, 1)];
+        assert_eq!(find_cycles(&waits).len(), 1);
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use file_lock_checker::{FileLockInfo, ProcessInfo};
+
+// คอลัมน์ที่เลือกได้ใน output แบบ CSV/TSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Column {
+    File,
+    Pid,
+    Name,
+    Path,
+    User,
+    // mode ที่แรงที่สุดของ lock ที่ถืออยู่ (WRITE ชนะ READ) ว่างถ้าแค่เปิดไฟล์
+    Mode,
+    // lock ทั้งหมดที่ถืออยู่ คั่นด้วย "; "
+    Locks,
+    // path ที่ process ใช้เปิดไฟล์ คั่นด้วย "; "
+    Opened,
+}
+
+// คอลัมน์ที่ใช้เมื่อไม่ได้ระบุ --columns
+pub const DEFAULT_COLUMNS: [Column; 6] = [
+    Column::File,
+    Column::Pid,
+    Column::Name,
+    Column::Path,
+    Column::User,
+    Column::Mode,
+];
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::File => "file",
+            Column::Pid => "pid",
+            Column::Name => "name",
+            Column::Path => "path",
+            Column::User => "user",
+            Column::Mode => "mode",
+            Column::Locks => "locks",
+            Column::Opened => "opened",
+        }
+    }
+
+    fn value(self, file_path: &str, process: &ProcessInfo) -> String {
+        match self {
+            Column::File => file_path.to_string(),
+            Column::Pid => process.pid.to_string(),
+            Column::Name => process.name.clone(),
+            Column::Path => process.path.clone(),
+            Column::User => process.user.clone().unwrap_or_default(),
+            Column::Mode => process
+                .strongest_lock_mode()
+                .map(|mode| mode.to_string())
+                .unwrap_or_default(),
+            Column::Locks => process
+                .locks
+                .iter()
+                .map(|lock| lock.to_string())
+                .collect::<Vec<_>>()
+                .join("; "),
+            Column::Opened => process.opened_paths().join("; "),
+        }
+    }
+}
+
+// สร้างตาราง header หนึ่งแถวตามด้วยหนึ่งแถวต่อ (ไฟล์, process)
+// separator เป็น ',' สำหรับ CSV หรือ '\t' สำหรับ TSV
+pub fn render_delimited(
+    lock_infos: &[FileLockInfo],
+    columns: &[Column],
+    separator: char,
+) -> String {
+    let mut out = String::new();
+    push_row(
+        &mut out,
+        columns.iter().map(|column| column.header().to_string()),
+        separator,
+    );
+
+    for lock_info in lock_infos {
+        for process in &lock_info.processes {
+            push_row(
+                &mut out,
+                columns
+                    .iter()
+                    .map(|column| column.value(&lock_info.file_path, process)),
+                separator,
+            );
+        }
+    }
+    out
+}
+
+fn push_row(out: &mut String, fields: impl Iterator<Item = String>, separator: char) {
+    let fields: Vec<String> = fields.map(|field| escape(&field, separator)).collect();
+    out.push_str(&fields.join(&separator.to_string()));
+    out.push('\n');
+}
+
+// CSV ใส่ quote ตาม RFC 4180 ส่วน TSV ไม่มี quote จึงแทน tab และขึ้นบรรทัดใหม่ด้วยช่องว่าง
+fn escape(field: &str, separator: char) -> String {
+    if separator == '\t' {
+        field.replace(['\t', '\r', '\n'], " ")
+    } else if field.contains([separator, '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_quotes_only_when_needed() {
+        assert_eq!(escape("plain", ','), "plain");
+        assert_eq!(escape("a,b", ','), "\"a,b\"");
+        assert_eq!(escape("say \"hi\"", ','), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape("two\nlines", ','), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn tsv_replaces_separators_with_spaces() {
+        assert_eq!(escape("a\tb\r\nc", '\t'), "a b  c");
+        assert_eq!(escape("a,\"b\"", '\t'), "a,\"b\"");
+    }
+}
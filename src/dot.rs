@@ -0,0 +1,106 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use file_lock_checker::{FileLockInfo, LockRecord};
+use std::collections::BTreeSet;
+
+// สร้าง Graphviz DOT ของไฟล์ process ที่ถือ lock และ process ที่รอ lock
+// หลายไฟล์จะอยู่ใน graph เดียว โดย process ที่ใช้หลายไฟล์มี node เดียว
+pub fn render_dot(lock_infos: &[FileLockInfo]) -> String {
+    let mut out = String::new();
+    out.push_str("digraph flc {\n");
+    out.push_str("    rankdir=LR;\n");
+    out.push_str("    node [fontname=\"monospace\"];\n");
+    for (i, lock_info) in lock_infos.iter().enumerate() {
+        out.push_str(&format!(
+            "    \"{}\" [shape=note, label=\"{}\"];\n",
+            file_node(i),
+            escape(&lock_info.file_path)
+        ));
+    }
+
+    // node ของ process ทั้งหมด (holder, opener และ waiter) แสดงครั้งเดียว
+    let mut nodes: BTreeSet<(u32, &str)> = BTreeSet::new();
+    for lock_info in lock_infos {
+        for process in &lock_info.processes {
+            nodes.insert((process.pid, &process.name));
+        }
+        for wait in &lock_info.waiters {
+            nodes.insert((wait.waiter_pid, &wait.waiter_name));
+            nodes.insert((wait.holder_pid, &wait.holder_name));
+        }
+    }
+    for (pid, name) in &nodes {
+        out.push_str(&format!(
+            "    \"pid_{}\" [shape=box, label=\"{}\\nPID {}\"];\n",
+            pid,
+            escape(name),
+            pid
+        ));
+    }
+
+    for (i, lock_info) in lock_infos.iter().enumerate() {
+        let file = file_node(i);
+
+        // process ที่ถือ lock ชี้ไปที่ไฟล์ ส่วนที่แค่เปิดไฟล์จะเป็นเส้นจุด
+        let waiting: BTreeSet<u32> = lock_info.waiters.iter().map(|w| w.waiter_pid).collect();
+        for process in &lock_info.processes {
+            if process.locks.is_empty() && !waiting.contains(&process.pid) {
+                out.push_str(&format!(
+                    "    \"pid_{}\" -> \"{}\" [label=\"open\", style=dotted];\n",
+                    process.pid, file
+                ));
+            }
+            for lock in &process.locks {
+                out.push_str(&format!(
+                    "    \"pid_{}\" -> \"{}\" [label=\"{}\"];\n",
+                    process.pid,
+                    file,
+                    edge_label(lock)
+                ));
+            }
+        }
+
+        // process ที่รอ lock ชี้ไปที่ไฟล์ และชี้ไปที่ process ที่ขวางอยู่
+        for wait in &lock_info.waiters {
+            out.push_str(&format!(
+                "    \"pid_{}\" -> \"{}\" [label=\"waits {}\", style=dashed, color=red];\n",
+                wait.waiter_pid,
+                file,
+                edge_label(&wait.requested)
+            ));
+            out.push_str(&format!(
+                "    \"pid_{}\" -> \"pid_{}\" [label=\"blocked by {}\", style=dashed, color=red];\n",
+                wait.waiter_pid,
+                wait.holder_pid,
+                edge_label(&wait.held)
+            ));
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+// id ของ node ไฟล์ ไฟล์เดียวใช้ "file" เหมือนเดิม
+fn file_node(index: usize) -> String {
+    if index == 0 {
+        "file".to_string()
+    } else {
+        format!("file_{}", index)
+    }
+}
+
+// label ของ edge เช่น "POSIX WRITE 0-EOF"
+fn edge_label(lock: &LockRecord) -> String {
+    let end = match lock.end {
+        Some(end) => end.to_string(),
+        None => "EOF".to_string(),
+    };
+    format!("{} {} {}-{}", lock.class, lock.mode, lock.start, end)
+}
+
+// escape อักขระพิเศษใน string ของ DOT (path บน Windows มี backslash)
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
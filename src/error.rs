@@ -0,0 +1,141 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+use std::io;
+
+// error ของ file-lock-checker แยกตามประเภท เพื่อให้ผู้เรียกตัดสินใจได้ว่าจะทำอย่างไรต่อ
+// อาจเพิ่มประเภทใหม่ได้ในอนาคต ผู้ใช้ library จึงต้องมี match arm สำรอง
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum FlcError {
+    // ไม่มีสิทธิ์ทำรายการ เช่น kill process ของ user อื่น
+    PermissionDenied {
+        action: String,
+        code: Option<i32>,
+    },
+    // process จบไปแล้วก่อนที่จะทำรายการได้
+    ProcessGone {
+        pid: u32,
+    },
+    // backend ของ platform ใช้งานไม่ได้ หรือไม่รองรับคำสั่งนี้
+    BackendUnavailable {
+        reason: String,
+        code: Option<i32>,
+    },
+    // สร้างหรือแก้ไข registry ไม่สำเร็จ
+    #[cfg_attr(not(windows), allow(dead_code))]
+    Registry {
+        action: String,
+        code: i32,
+    },
+    // I/O error อื่นๆ
+    Io {
+        context: String,
+        source: io::Error,
+    },
+}
+
+impl FlcError {
+    // สร้าง error จาก io::Error โดยแยกกรณีไม่มีสิทธิ์ออกมา
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        let context = context.into();
+        if source.kind() == io::ErrorKind::PermissionDenied {
+            FlcError::PermissionDenied {
+                action: context,
+                code: source.raw_os_error(),
+            }
+        } else {
+            FlcError::Io { context, source }
+        }
+    }
+
+    // backend ไม่รองรับคำสั่งนี้
+    pub fn unsupported(reason: impl Into<String>) -> Self {
+        FlcError::BackendUnavailable {
+            reason: reason.into(),
+            code: None,
+        }
+    }
+
+    // ชื่อประเภทของ error ที่ใช้ใน JSON เช่น "permission_denied"
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FlcError::PermissionDenied { .. } => "permission_denied",
+            FlcError::ProcessGone { .. } => "process_gone",
+            FlcError::BackendUnavailable { .. } => "backend_unavailable",
+            FlcError::Registry { .. } => "registry",
+            FlcError::Io { .. } => "io",
+        }
+    }
+
+    // error code ของ OS ถ้ามี
+    pub fn os_code(&self) -> Option<i32> {
+        match self {
+            FlcError::PermissionDenied { code, .. } => *code,
+            FlcError::ProcessGone { .. } => None,
+            FlcError::BackendUnavailable { code, .. } => *code,
+            FlcError::Registry { code, .. } => Some(*code),
+            FlcError::Io { source, .. } => source.raw_os_error(),
+        }
+    }
+}
+
+impl fmt::Display for FlcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlcError::PermissionDenied { action, code } => {
+                write!(f, "Permission denied: {}", action)?;
+                write_os_code(f, *code)
+            }
+            FlcError::ProcessGone { pid } => {
+                write!(f, "Process {} no longer exists", pid)
+            }
+            FlcError::BackendUnavailable { reason, code } => {
+                write!(f, "{}", reason)?;
+                write_os_code(f, *code)
+            }
+            FlcError::Registry { action, code } => {
+                write!(f, "Registry error: {}", action)?;
+                write_os_code(f, Some(*code))
+            }
+            FlcError::Io { context, source } => write!(f, "{}: {}", context, source),
+        }
+    }
+}
+
+impl std::error::Error for FlcError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FlcError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// ใน JSON แสดงเป็น {"kind": ..., "message": ..., "os_code": ...}
+impl Serialize for FlcError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("FlcError", 3)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("os_code", &self.os_code())?;
+        state.end()
+    }
+}
+
+// แสดง error code พร้อมข้อความจาก OS เช่น " (os error 5: Access is denied.)"
+fn write_os_code(f: &mut fmt::Formatter<'_>, code: Option<i32>) -> fmt::Result {
+    match code {
+        Some(code) => {
+            let message = io::Error::from_raw_os_error(code).to_string();
+            // io::Error แสดงเป็น "ข้อความ (os error N)" ให้ตัดส่วนท้ายออก
+            let message = message
+                .strip_suffix(&format!(" (os error {})", code))
+                .unwrap_or(&message);
+            write!(f, " (os error {}: {})", code, message)
+        }
+        None => Ok(()),
+    }
+}
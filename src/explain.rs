@@ -0,0 +1,306 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use crate::error::FlcError;
+use crate::probe::{AccessMode, AccessProbe, probe_modes};
+use crate::process::check_file_locks;
+use crate::types::{HandleKind, LockClass};
+
+// สาเหตุหนึ่งข้อที่ทำให้แก้ไขหรือลบไฟล์ไม่ได้
+#[derive(Debug)]
+pub struct Finding {
+    pub reason: &'static str,
+    pub detail: String,
+}
+
+// ผลการวิเคราะห์ทั้งหมดของไฟล์
+#[derive(Debug)]
+pub struct Explanation {
+    pub probes: Vec<AccessProbe>,
+    // สาเหตุที่ตรวจพบ
+    pub findings: Vec<Finding>,
+    // รายการที่ตรวจแล้วทั้งหมด ไม่ว่าจะพบหรือไม่
+    pub checked: Vec<&'static str>,
+}
+
+// การทดสอบ read ต้องเปิดไฟล์จริง ซึ่งจะ break write lease ที่ explain กำลังจะรายงานเป็นสาเหตุ
+// จึงทดสอบเฉพาะรูปแบบที่ไม่เปิดไฟล์ ส่วน lease ดูจากผลของ backend แทน
+const EXPLAIN_MODES: [AccessMode; 5] = [
+    AccessMode::Write,
+    AccessMode::Append,
+    AccessMode::Rename,
+    AccessMode::Delete,
+    AccessMode::Exec,
+];
+
+// ตรวจทุกสาเหตุที่ทำให้เขียนหรือลบไฟล์ไม่ได้
+pub fn explain(file_path: &str) -> Result<Explanation, FlcError> {
+    std::fs::symlink_metadata(file_path)
+        .map_err(|e| FlcError::io(format!("Failed to stat '{}'", file_path), e))?;
+
+    let mut findings = Vec::new();
+    let mut checked = Vec::new();
+
+    platform::check(file_path, &mut findings, &mut checked);
+
+    // lease, advisory lock และ handle ที่เปิดอยู่ ดึงจาก backend ของ platform
+    checked.extend(["leases", "advisory locks", "open handles"]);
+    let lock_info = check_file_locks(file_path)?;
+    // process ที่รันไฟล์นี้อยู่ได้มาจาก handle แบบ exe ในผลของ backend (มีเฉพาะบน Linux)
+    for process in &lock_info.processes {
+        if process
+            .handles
+            .iter()
+            .any(|handle| handle.kind == HandleKind::Exe)
+        {
+            findings.push(Finding {
+                reason: "running executable",
+                detail: format!(
+                    "file is being executed by {} (PID: {}), writes fail with ETXTBSY",
+                    process.name, process.pid
+                ),
+            });
+        }
+    }
+    for process in &lock_info.processes {
+        for lock in &process.locks {
+            let reason = if lock.class == LockClass::Lease {
+                "lease"
+            } else {
+                "advisory lock"
+            };
+            findings.push(Finding {
+                reason,
+                detail: format!("{} (PID: {}) holds {}", process.name, process.pid, lock),
+            });
+        }
+    }
+    if !lock_info.processes.is_empty() {
+        let holders: Vec<String> = lock_info
+            .processes
+            .iter()
+            .map(|process| format!("{} (PID: {})", process.name, process.pid))
+            .collect();
+        findings.push(Finding {
+            reason: "open handles",
+            detail: format!(
+                "opened by {} process(es): {}",
+                holders.len(),
+                holders.join(", ")
+            ),
+        });
+    }
+
+    Ok(Explanation {
+        probes: probe_modes(file_path, &EXPLAIN_MODES),
+        findings,
+        checked,
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::Finding;
+    use crate::probe::{FS_APPEND_FL, FS_IMMUTABLE_FL, access, inode_flags, mount_flags};
+    use std::ffi::CString;
+    use std::fs;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+    use std::path::Path;
+
+    // tag ของ entry ใน system.posix_acl_access (linux/posix_acl_xattr.h)
+    const ACL_USER_OBJ: u16 = 0x01;
+    const ACL_USER: u16 = 0x02;
+    const ACL_GROUP_OBJ: u16 = 0x04;
+    const ACL_GROUP: u16 = 0x08;
+    const ACL_MASK: u16 = 0x10;
+    const ACL_OTHER: u16 = 0x20;
+
+    pub fn check(file_path: &str, findings: &mut Vec<Finding>, checked: &mut Vec<&'static str>) {
+        let path = Path::new(file_path);
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+
+        checked.extend([
+            "ownership and mode bits",
+            "POSIX ACLs",
+            "immutable/append-only attributes",
+            "read-only mounts",
+            "running executables",
+        ]);
+
+        check_permissions(path, "file", libc::W_OK, findings);
+        check_permissions(
+            parent,
+            "parent directory",
+            libc::W_OK | libc::X_OK,
+            findings,
+        );
+        check_sticky(path, parent, findings);
+
+        if let Ok(flags) = inode_flags(path) {
+            if flags & FS_IMMUTABLE_FL != 0 {
+                findings.push(Finding {
+                    reason: "immutable attribute",
+                    detail: "file is immutable and cannot be modified, renamed or deleted \
+                             (clear with 'chattr -i')"
+                        .to_string(),
+                });
+            }
+            if flags & FS_APPEND_FL != 0 {
+                findings.push(Finding {
+                    reason: "append-only attribute",
+                    detail: "file can only be appended to (clear with 'chattr -a')".to_string(),
+                });
+            }
+        }
+
+        if mount_flags(path).is_ok_and(|flags| flags & libc::ST_RDONLY != 0) {
+            findings.push(Finding {
+                reason: "read-only mount",
+                detail: "file is on a filesystem mounted read-only".to_string(),
+            });
+        }
+    }
+
+    // ตรวจสิทธิ์ด้วย access() แล้วบอกว่าเป็นเพราะ mode bits หรือ ACL
+    fn check_permissions(path: &Path, what: &str, mode: libc::c_int, findings: &mut Vec<Finding>) {
+        let Err(error) = access(path, mode) else {
+            return;
+        };
+        if error.raw_os_error() != Some(libc::EACCES) {
+            return;
+        }
+        let Ok(metadata) = fs::metadata(path) else {
+            return;
+        };
+
+        let needed = if mode & libc::X_OK != 0 {
+            "write and search"
+        } else {
+            "write"
+        };
+        let acl = read_acl(path);
+        if acl.len() > 3 {
+            findings.push(Finding {
+                reason: "POSIX ACL",
+                detail: format!(
+                    "ACL on {} '{}' denies {} access: {}",
+                    what,
+                    path.display(),
+                    needed,
+                    acl.join(",")
+                ),
+            });
+        } else {
+            findings.push(Finding {
+                reason: "ownership and mode bits",
+                detail: format!(
+                    "no {} permission on {} '{}' (owner {}, group {}, mode {:04o}; running as uid {})",
+                    needed,
+                    what,
+                    path.display(),
+                    metadata.uid(),
+                    metadata.gid(),
+                    metadata.mode() & 0o7777,
+                    unsafe { libc::geteuid() }
+                ),
+            });
+        }
+    }
+
+    // directory ที่มี sticky bit ลบได้เฉพาะเจ้าของไฟล์ เจ้าของ directory หรือ root
+    fn check_sticky(path: &Path, parent: &Path, findings: &mut Vec<Finding>) {
+        let (Ok(metadata), Ok(parent_metadata)) =
+            (fs::symlink_metadata(path), fs::metadata(parent))
+        else {
+            return;
+        };
+        let euid = unsafe { libc::geteuid() };
+        if parent_metadata.mode() & libc::S_ISVTX != 0
+            && euid != 0
+            && euid != metadata.uid()
+            && euid != parent_metadata.uid()
+        {
+            findings.push(Finding {
+                reason: "ownership and mode bits",
+                detail: format!(
+                    "parent directory '{}' has the sticky bit set and the file is owned by uid {}",
+                    parent.display(),
+                    metadata.uid()
+                ),
+            });
+        }
+    }
+
+    // อ่าน POSIX ACL ในรูปแบบเดียวกับ getfacl เช่น "user:1000:rw-"
+    fn read_acl(path: &Path) -> Vec<String> {
+        let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+            return Vec::new();
+        };
+        let mut buffer = [0u8; 4096];
+        let size = unsafe {
+            libc::getxattr(
+                c_path.as_ptr(),
+                c"system.posix_acl_access".as_ptr(),
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+            )
+        };
+        if size < 4 {
+            return Vec::new();
+        }
+
+        // header 4 byte ตามด้วย entry ละ 8 byte: tag (u16), perm (u16), id (u32)
+        buffer[4..size as usize]
+            .chunks_exact(8)
+            .map(|entry| {
+                let tag = u16::from_le_bytes([entry[0], entry[1]]);
+                let perm = u16::from_le_bytes([entry[2], entry[3]]);
+                let id = u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]);
+                let perms = format!(
+                    "{}{}{}",
+                    if perm & 4 != 0 { 'r' } else { '-' },
+                    if perm & 2 != 0 { 'w' } else { '-' },
+                    if perm & 1 != 0 { 'x' } else { '-' }
+                );
+                match tag {
+                    ACL_USER_OBJ => format!("user::{}", perms),
+                    ACL_USER => format!("user:{}:{}", id, perms),
+                    ACL_GROUP_OBJ => format!("group::{}", perms),
+                    ACL_GROUP => format!("group:{}:{}", id, perms),
+                    ACL_MASK => format!("mask::{}", perms),
+                    ACL_OTHER => format!("other::{}", perms),
+                    _ => format!("unknown:{}:{}", id, perms),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::Finding;
+    use std::fs;
+
+    pub fn check(file_path: &str, findings: &mut Vec<Finding>, checked: &mut Vec<&'static str>) {
+        checked.push("read-only attribute");
+
+        if fs::metadata(file_path).is_ok_and(|metadata| metadata.permissions().readonly()) {
+            findings.push(Finding {
+                reason: "read-only attribute",
+                detail: "file has the read-only attribute set (clear with 'attrib -r')".to_string(),
+            });
+        }
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+mod platform {
+    use super::Finding;
+
+    pub fn check(_file_path: &str, _findings: &mut Vec<Finding>, _checked: &mut Vec<&'static str>) {
+    }
+}
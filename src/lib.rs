@@ -0,0 +1,38 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+//! Find out which processes are holding a file open or locked, and optionally
+//! terminate them.
+//!
+//! Detection goes through the Restart Manager API on Windows and through `/proc`
+//! on Linux. The `file-lock-checker` binary is a thin command line front end over
+//! this crate.
+//!
+//! ```no_run
+//! let info = file_lock_checker::check_file_locks("build/app.dll")?;
+//! for process in &info.processes {
+//!     println!("{} (PID: {})", process.name, process.pid);
+//! }
+//! # Ok::<(), file_lock_checker::FlcError>(())
+//! ```
+
+mod backend;
+pub mod deadlock;
+mod error;
+pub mod explain;
+pub mod probe;
+mod process;
+pub mod report;
+mod types;
+#[cfg(windows)]
+mod utils;
+
+pub use error::FlcError;
+pub use process::{
+    check_file_locks, check_inode_locks, check_many_file_locks, deleted_files, kill_process,
+    lock_waits, probe_lock, processes_changed,
+};
+pub use types::{
+    DeletedFile, DeletedFileHolder, DeletedFiles, FileLockInfo, Filesystem, HandleKind, LockClass,
+    LockConflict, LockMode, LockRecord, LockRequest, LockWait, OpenHandle, ProcessInfo,
+};
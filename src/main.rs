@@ -2,90 +2,518 @@
 // SPDX-License-Identifier: MIT
 
 mod commands;
+mod compat;
+#[cfg(windows)]
 mod context_menu;
-mod process;
+mod delimited;
+mod dot;
+mod ranges;
+#[cfg(windows)]
 mod registry;
-mod types;
-mod utils;
+mod targets;
+mod template;
+mod tree;
 
-use std::env;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use commands::{CheckOptions, ExecOptions, OutputFormat, Status, Verbosity};
+use compat::Compat;
+use delimited::{Column, DEFAULT_COLUMNS};
+use file_lock_checker::{LockClass, LockMode, LockRequest};
+use glob::Pattern;
+use std::io;
+use std::process::ExitCode;
+use template::Template;
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+/// Find which processes are locking a file, and unlock it by terminating them
+#[derive(Debug, Parser)]
+#[command(
+    name = "file-lock-checker",
+    version,
+    after_help = "Exit status: 0 not locked, 1 locked, 2 not locked but some processes could not be inspected, 3 error"
+)]
+struct Cli {
+    /// Output format (check supports all, unlock text/json, monitor text/json/ndjson)
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
 
-    if args.len() < 2 {
-        print_usage(&args[0]);
-        return;
-    }
+    /// Print only results and errors, without banners and hints
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
 
-    let command = &args[1];
+    /// Print every access probe result in addition to the normal output
+    #[arg(short, long, global = true)]
+    verbose: bool,
 
-    match command.as_str() {
-        "install" => match context_menu::install() {
-            Ok(_) => println!("Context menu installed successfully!"),
-            Err(e) => eprintln!("Failed to install context menu: {}", e),
-        },
-        "uninstall" => match context_menu::uninstall() {
-            Ok(_) => println!("Context menu uninstalled successfully!"),
-            Err(e) => eprintln!("Failed to uninstall context menu: {}", e),
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Install context menu entries (Windows, run as Administrator)
+    Install,
+
+    /// Remove context menu entries (Windows, run as Administrator)
+    Uninstall,
+
+    /// Check which processes are locking the files
+    Check {
+        /// Files, directories or glob patterns such as '**/*.dll' to check, @FILE reads one path per line from FILE
+        #[arg(required_unless_present_any = ["null", "inode"])]
+        paths: Vec<String>,
+
+        /// Check a file by device and inode, as printed by 'stat -c %d:%i', for files with no path left
+        #[arg(
+            long,
+            value_name = "DEV:INO",
+            value_parser = parse_inode,
+            conflicts_with_all = ["paths", "null", "recursive"]
+        )]
+        inode: Vec<(u64, u64)>,
+
+        /// Skip paths matching this glob, and everything under matching directories
+        #[arg(long, value_name = "PATTERN")]
+        exclude: Vec<Pattern>,
+
+        /// Also read NUL-separated paths from stdin, as written by find -print0
+        #[arg(short = '0', long)]
+        null: bool,
+
+        /// Check every file and directory under the given directories, grouped by process
+        #[arg(short, long)]
+        recursive: bool,
+
+        /// With --recursive, also print a tree of the paths in use
+        #[arg(long, requires = "recursive")]
+        tree: bool,
+
+        /// Columns for --format csv/tsv, comma separated
+        #[arg(long, value_enum, value_delimiter = ',', default_values_t = DEFAULT_COLUMNS)]
+        columns: Vec<Column>,
+
+        /// Render with a template such as '{file}\t{pid}\t{name}' in place of --format
+        #[arg(long, conflicts_with = "compat")]
+        template: Option<Template>,
+
+        /// Print like 'lsof -F' or 'fuser -v' in place of --format
+        #[arg(long, value_enum)]
+        compat: Option<Compat>,
+
+        /// Exit without waiting for enter (implied when stdin is not a terminal)
+        #[arg(long)]
+        no_pause: bool,
+    },
+
+    /// Kill processes that are locking the file
+    Unlock {
+        /// File or glob pattern to unlock
+        path: String,
+
+        /// Skip paths matching this glob, and everything under matching directories
+        #[arg(long, value_name = "PATTERN")]
+        exclude: Vec<Pattern>,
+
+        /// Kill without asking (required when stdin is not a terminal)
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Monitor file locks in real-time
+    Monitor {
+        /// File, directory or glob pattern to monitor
+        path: String,
+
+        /// Skip paths matching this glob, and everything under matching directories
+        #[arg(long, value_name = "PATTERN")]
+        exclude: Vec<Pattern>,
+
+        /// Seconds between checks
+        #[arg(default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+
+    /// Wait until no process holds the file
+    WaitUnlocked {
+        /// File to wait for
+        path: String,
+
+        /// Give up after this many seconds and print the remaining holders
+        #[arg(long)]
+        timeout: Option<u64>,
+
+        /// Seconds between checks
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+
+    /// Run a command once the files are free, retrying when a process gets in the way
+    Exec {
+        /// File that must be free before running, repeat for several files
+        #[arg(long = "path", value_name = "PATH", required = true)]
+        paths: Vec<String>,
+
+        /// Times to rerun the command when it fails while a process holds a file
+        #[arg(long, default_value_t = 3)]
+        retries: u32,
+
+        /// Seconds before the first retry, doubled on every retry
+        #[arg(long, default_value_t = 1)]
+        backoff: u64,
+
+        /// Give up waiting for the files after this many seconds (exit code 124)
+        #[arg(long)]
+        timeout: Option<u64>,
+
+        /// Seconds between checks while waiting
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+
+        /// Command to run, after --
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Show which byte ranges are locked and by whom
+    Ranges {
+        /// File to inspect
+        path: String,
+    },
+
+    /// Check whether a lock would be granted, without taking it
+    ProbeLock {
+        /// File to probe
+        path: String,
+
+        /// Lock type: shared or exclusive
+        #[arg(value_parser = parse_lock_mode)]
+        mode: LockMode,
+
+        /// Lock flavour: posix, ofd or flock
+        #[arg(default_value = "posix", value_parser = parse_lock_class)]
+        flavour: LockClass,
+
+        /// First byte of the range
+        #[arg(default_value_t = 0)]
+        start: u64,
+
+        /// Length of the range, 0 means up to EOF
+        #[arg(default_value_t = 0)]
+        length: u64,
+    },
+
+    /// Explain every reason the file cannot be modified or deleted
+    Explain {
+        /// File to explain
+        path: String,
+    },
+
+    /// Detect processes waiting on each other's locks
+    Deadlock,
+
+    /// List deleted files that are still open and use disk space, largest first
+    Deleted,
+
+    /// Print the JSON schema used by --format json
+    Schema,
+
+    /// Print a shell completion script
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::try_parse().unwrap_or_else(|e| usage_error(e));
+
+    commands::set_verbosity(if cli.quiet {
+        Verbosity::Quiet
+    } else if cli.verbose {
+        Verbosity::Verbose
+    } else {
+        Verbosity::Normal
+    });
+
+    match cli.command {
+        #[cfg(windows)]
+        Command::Install => match context_menu::install() {
+            Ok(_) => {
+                println!("Context menu installed successfully!");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("Failed to install context menu: {}", e);
+                Status::Error.into()
+            }
         },
-        "check" => {
-            if args.len() != 3 {
-                println!("Usage: {} check <file_path>", args[0]);
-                return;
+        #[cfg(windows)]
+        Command::Uninstall => match context_menu::uninstall() {
+            Ok(_) => {
+                println!("Context menu uninstalled successfully!");
+                ExitCode::SUCCESS
             }
-            commands::check(&args[2]);
-        }
-        "unlock" => {
-            if args.len() != 3 {
-                println!("Usage: {} unlock <file_path>", args[0]);
-                return;
+            Err(e) => {
+                eprintln!("Failed to uninstall context menu: {}", e);
+                Status::Error.into()
             }
-            commands::unlock(&args[2]);
+        },
+        #[cfg(not(windows))]
+        Command::Install | Command::Uninstall => {
+            eprintln!("Context menu integration is only available on Windows");
+            Status::Error.into()
         }
-        "monitor" => {
-            if args.len() < 3 {
-                println!("Usage: {} monitor <file_path> [interval_seconds]", args[0]);
-                return;
+        Command::Check {
+            paths,
+            inode,
+            exclude,
+            null,
+            recursive,
+            tree,
+            columns,
+            template,
+            compat,
+            no_pause,
+        } => {
+            let options = CheckOptions {
+                format: cli.format,
+                pause: !no_pause,
+                columns,
+                template,
+                compat,
+                tree,
+            };
+            if !inode.is_empty() {
+                return commands::check_inodes(&inode, &options).into();
             }
-            let interval = if args.len() >= 4 {
-                args[3].parse().unwrap_or(2)
+            let many = targets::is_many(&paths, null);
+            let paths = targets::collect(&paths, null)
+                .unwrap_or_else(|e| usage_error(Cli::command().error(ErrorKind::Io, e)));
+            let paths = resolve(&paths, &exclude);
+            if recursive {
+                let mut found = walk_roots(&paths);
+                targets::exclude(&mut found, &exclude);
+                commands::check_many(&found, Some(&paths), &options).into()
+            } else if many {
+                commands::check_many(&paths, None, &options).into()
             } else {
-                2
+                commands::check(&paths[0], &options).into()
+            }
+        }
+        Command::Unlock { path, exclude, yes } => {
+            require_format(
+                "unlock",
+                cli.format,
+                &[OutputFormat::Text, OutputFormat::Json],
+            );
+            let paths = resolve(&[path], &exclude);
+            if paths.len() == 1 {
+                commands::unlock(&paths[0], cli.format, yes).into()
+            } else {
+                require_format(
+                    "unlock with several files",
+                    cli.format,
+                    &[OutputFormat::Text],
+                );
+                commands::unlock_many(&paths, yes).into()
+            }
+        }
+        Command::Monitor {
+            path,
+            exclude,
+            interval,
+        } => {
+            require_format(
+                "monitor",
+                cli.format,
+                &[OutputFormat::Text, OutputFormat::Json, OutputFormat::Ndjson],
+            );
+            commands::monitor(&resolve(&[path], &exclude), interval, cli.format);
+            ExitCode::SUCCESS
+        }
+        Command::WaitUnlocked {
+            path,
+            timeout,
+            interval,
+        } => {
+            require_format(
+                "wait-unlocked",
+                cli.format,
+                &[OutputFormat::Text, OutputFormat::Json],
+            );
+            commands::wait_unlocked(&path, timeout, interval, cli.format).into()
+        }
+        Command::Exec {
+            paths,
+            retries,
+            backoff,
+            timeout,
+            interval,
+            command,
+        } => {
+            require_format("exec", cli.format, &[OutputFormat::Text]);
+            let options = ExecOptions {
+                file_paths: paths,
+                retries,
+                backoff_seconds: backoff,
+                timeout_seconds: timeout,
+                interval_seconds: interval,
+            };
+            commands::exec(&command, &options)
+        }
+        Command::Ranges { path } => {
+            require_format("ranges", cli.format, &[OutputFormat::Text]);
+            commands::ranges(&path).into()
+        }
+        Command::ProbeLock {
+            path,
+            mode,
+            flavour,
+            start,
+            length,
+        } => {
+            require_format("probe-lock", cli.format, &[OutputFormat::Text]);
+            check_range(start, length);
+            let request = LockRequest {
+                class: flavour,
+                mode,
+                start,
+                len: length,
             };
-            commands::monitor(&args[2], interval);
+            commands::probe(&path, &request).into()
+        }
+        Command::Explain { path } => {
+            require_format("explain", cli.format, &[OutputFormat::Text]);
+            commands::explain(&path).into()
+        }
+        Command::Deadlock => {
+            require_format("deadlock", cli.format, &[OutputFormat::Text]);
+            commands::deadlock().into()
         }
-        _ => {
-            print_usage(&args[0]);
+        Command::Deleted => {
+            require_format(
+                "deleted",
+                cli.format,
+                &[OutputFormat::Text, OutputFormat::Json],
+            );
+            commands::deleted(cli.format).into()
         }
+        Command::Schema => {
+            print!("{}", file_lock_checker::report::SCHEMA);
+            ExitCode::SUCCESS
+        }
+        Command::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut Cli::command(),
+                "file-lock-checker",
+                &mut io::stdout(),
+            );
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+// ช่วง byte ต้องอยู่ใน off_t ซึ่งเป็นเลขมีเครื่องหมาย 64 bit ไม่เช่นนั้น kernel จะปฏิเสธหรือคำนวณล้น
+fn check_range(start: u64, length: u64) {
+    let last = start.checked_add(length.saturating_sub(1));
+    if start > i64::MAX as u64 || last.is_none_or(|last| last > i64::MAX as u64) {
+        usage_error(Cli::command().error(
+            ErrorKind::ValueValidation,
+            format!(
+                "Range {}+{} does not fit in a file offset (at most {})",
+                start,
+                length,
+                i64::MAX
+            ),
+        ));
     }
 }
 
-fn print_usage(program_name: &str) {
-    println!("File Lock Checker v1.0");
-    println!("Usage:");
-    println!(
-        "  {} install   - Install context menu entries (Note: Must run as Administrator!)",
-        program_name
-    );
-    println!("  {} uninstall - Remove context menu entries (Note: Must run as Administrator!)", program_name);
-    println!(
-        "  {} check <file_path>                    - Check which processes are locking the file",
-        program_name
-    );
-    println!(
-        "  {} unlock <file_path>                   - Kill processes that are locking the file",
-        program_name
-    );
-    println!(
-        "  {} monitor <file_path> [interval]       - Monitor file locks in real-time (default: 2 seconds)",
-        program_name
-    );
-    println!();
-    println!("Examples:");
-    println!("  {} install", program_name);
-    println!("  {} uninstall", program_name);
-    println!("  {} check \"C:\\temp\\locked_file.txt\"", program_name);
-    println!("  {} unlock \"C:\\temp\\locked_file.txt\"", program_name);
-    println!("  {} monitor \"C:\\temp\\locked_file.txt\" 5", program_name);
-}
\ No newline at end of file
+// แทน glob ด้วย path ที่ match แล้วตัด --exclude ออก ก่อนเริ่มตรวจ
+fn resolve(args: &[String], exclude: &[Pattern]) -> Vec<String> {
+    let (mut paths, skipped) = targets::expand(args)
+        .unwrap_or_else(|e| usage_error(Cli::command().error(ErrorKind::Io, e)));
+    for (dir, e) in skipped {
+        eprintln!("Skipping '{}': {}", dir, e);
+    }
+    let found = paths.len();
+    targets::exclude(&mut paths, exclude);
+    if paths.is_empty() && !args.is_empty() {
+        // glob ที่เจอแต่ directory ที่อ่านไม่ได้ก็ไม่เหลือ path แม้ไม่มี --exclude
+        let message = if found > 0 {
+            "Every path was excluded by --exclude".to_string()
+        } else {
+            format!("No paths match '{}'", args.join("', '"))
+        };
+        usage_error(Cli::command().error(ErrorKind::ValueValidation, message));
+    }
+    paths
+}
+
+// path ทั้งหมดใต้ทุก root โดยไม่ซ้ำกัน directory ที่อ่านไม่ได้จะแจ้งทาง stderr
+fn walk_roots(roots: &[String]) -> Vec<String> {
+    let mut paths = Vec::new();
+    for root in roots {
+        let (found, skipped) = targets::walk(root);
+        for (dir, e) in skipped {
+            eprintln!("Skipping '{}': {}", dir, e);
+        }
+        paths.extend(found);
+    }
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+// clap ออกด้วย code 2 เมื่อ argument ผิด ซึ่งชนกับ Status::Partial จึงใช้ Status::Error แทน
+fn usage_error(error: clap::Error) -> ! {
+    let _ = error.print();
+    // --help และ --version ไม่ใช่ error
+    if !error.use_stderr() {
+        std::process::exit(0);
+    }
+    std::process::exit(Status::Error as i32);
+}
+
+// ออกพร้อมข้อความของ clap ถ้าคำสั่งไม่รองรับ format ที่เลือก
+fn require_format(command: &str, format: OutputFormat, supported: &[OutputFormat]) {
+    if supported.contains(&format) {
+        return;
+    }
+    let names: Vec<String> = supported.iter().map(|f| f.to_string()).collect();
+    usage_error(Cli::command().error(
+        ErrorKind::InvalidValue,
+        format!(
+            "'{}' does not support --format {} (expected {})",
+            command,
+            format,
+            names.join(", ")
+        ),
+    ));
+}
+
+// แยก "DEV:INO" เป็นเลข device และ inode แบบฐานสิบ
+fn parse_inode(s: &str) -> Result<(u64, u64), String> {
+    let error = || format!("'{}' is not DEV:INO (for example 2049:1835011)", s);
+    let (dev, ino) = s.split_once(':').ok_or_else(error)?;
+    Ok((
+        dev.parse().map_err(|_| error())?,
+        ino.parse().map_err(|_| error())?,
+    ))
+}
+
+fn parse_lock_mode(s: &str) -> Result<LockMode, String> {
+    s.parse()
+}
+
+fn parse_lock_class(s: &str) -> Result<LockClass, String> {
+    match s.parse()? {
+        LockClass::Lease => {
+            Err("Leases cannot be probed (expected posix, ofd or flock)".to_string())
+        }
+        class => Ok(class),
+    }
+}
@@ -0,0 +1,367 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use std::fmt;
+use std::io;
+
+// ใช้ภายใน explain เท่านั้น ผู้ใช้ library ได้ผลลัพธ์ผ่าน Explanation
+#[cfg(target_os = "linux")]
+pub(crate) use platform::{FS_APPEND_FL, FS_IMMUTABLE_FL, access, inode_flags, mount_flags};
+
+// รูปแบบการเข้าถึงไฟล์ที่ทดสอบ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessMode {
+    Read,
+    Write,
+    Append,
+    Rename,
+    Delete,
+    Exec,
+}
+
+pub const ACCESS_MODES: [AccessMode; 6] = [
+    AccessMode::Read,
+    AccessMode::Write,
+    AccessMode::Append,
+    AccessMode::Rename,
+    AccessMode::Delete,
+    AccessMode::Exec,
+];
+
+// กลุ่มของ error ที่ทำให้เข้าถึงไม่ได้
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DenialClass {
+    // EACCES: สิทธิ์ของไฟล์หรือ directory ไม่พอ
+    PermissionDenied,
+    // EBUSY: ไฟล์ถูกใช้งานอยู่ เช่น mount point หรือ sharing violation บน Windows
+    Busy,
+    // ETXTBSY: executable ที่กำลังรันอยู่
+    TextBusy,
+    // EROFS: filesystem เป็นแบบอ่านอย่างเดียว
+    ReadOnlyFs,
+    // EPERM: ถูกห้ามโดย attribute เช่น immutable หรือ sticky bit
+    NotPermitted,
+    // EWOULDBLOCK: มี lease หรือ lock ขวางอยู่
+    WouldBlock,
+    Other,
+}
+
+// ผลการทดสอบที่เข้าถึงไม่ได้
+#[derive(Debug)]
+pub struct Denial {
+    pub class: DenialClass,
+    pub error: io::Error,
+}
+
+// ผลการทดสอบของแต่ละรูปแบบการเข้าถึง
+#[derive(Debug)]
+pub struct AccessProbe {
+    pub mode: AccessMode,
+    pub outcome: Result<(), Denial>,
+}
+
+impl fmt::Display for AccessMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AccessMode::Read => "read",
+            AccessMode::Write => "write",
+            AccessMode::Append => "append",
+            AccessMode::Rename => "rename",
+            AccessMode::Delete => "delete",
+            AccessMode::Exec => "exec",
+        };
+        f.pad(name)
+    }
+}
+
+impl fmt::Display for DenialClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DenialClass::PermissionDenied => "EACCES",
+            DenialClass::Busy => "EBUSY",
+            DenialClass::TextBusy => "ETXTBSY",
+            DenialClass::ReadOnlyFs => "EROFS",
+            DenialClass::NotPermitted => "EPERM",
+            DenialClass::WouldBlock => "EWOULDBLOCK",
+            DenialClass::Other => "ERROR",
+        };
+        f.pad(name)
+    }
+}
+
+impl fmt::Display for Denial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.class, self.error)
+    }
+}
+
+impl From<io::Error> for Denial {
+    fn from(error: io::Error) -> Self {
+        Denial {
+            class: classify(&error),
+            error,
+        }
+    }
+}
+
+// ทดสอบการเข้าถึงไฟล์ทุกรูปแบบโดยไม่แก้ไขไฟล์
+pub fn probe_access(file_path: &str) -> Vec<AccessProbe> {
+    probe_modes(file_path, &ACCESS_MODES)
+}
+
+// ทดสอบเฉพาะรูปแบบการเข้าถึงที่เลือก
+pub fn probe_modes(file_path: &str, modes: &[AccessMode]) -> Vec<AccessProbe> {
+    modes
+        .iter()
+        .map(|&mode| AccessProbe {
+            mode,
+            outcome: platform::probe(file_path, mode).map_err(Denial::from),
+        })
+        .collect()
+}
+
+// จัดกลุ่ม error ตาม errno (Linux) หรือ error code (Windows)
+fn classify(error: &io::Error) -> DenialClass {
+    platform::classify(error).unwrap_or(match error.kind() {
+        io::ErrorKind::PermissionDenied => DenialClass::PermissionDenied,
+        io::ErrorKind::WouldBlock => DenialClass::WouldBlock,
+        io::ErrorKind::ReadOnlyFilesystem => DenialClass::ReadOnlyFs,
+        io::ErrorKind::ResourceBusy => DenialClass::Busy,
+        io::ErrorKind::ExecutableFileBusy => DenialClass::TextBusy,
+        _ => DenialClass::Other,
+    })
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{AccessMode, DenialClass};
+    use crate::backend::proc_locks::read_proc_locks;
+    use crate::types::LockClass;
+    use std::ffi::CString;
+    use std::fs::{self, OpenOptions};
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+    use std::path::Path;
+
+    // attribute ของ inode จาก statx ซึ่งมีค่าเดียวกับ flag ของ FS_IOC_GETFLAGS (linux/fs.h)
+    pub const FS_IMMUTABLE_FL: u64 = libc::STATX_ATTR_IMMUTABLE as u64;
+    pub const FS_APPEND_FL: u64 = libc::STATX_ATTR_APPEND as u64;
+
+    pub fn probe(file_path: &str, mode: AccessMode) -> io::Result<()> {
+        let path = Path::new(file_path);
+        let is_dir = fs::metadata(path)?.is_dir();
+
+        match mode {
+            AccessMode::Read if is_dir => fs::read_dir(path).map(|_| ()),
+            AccessMode::Read => open_read(path),
+            // directory เปิดเขียนไม่ได้ จึงใช้ access() แทน
+            AccessMode::Write | AccessMode::Append if is_dir => access(path, libc::W_OK),
+            AccessMode::Write => check_write(path, false),
+            AccessMode::Append => check_write(path, true),
+            AccessMode::Rename => {
+                // rename ไปยังชื่อเดิมไม่เปลี่ยนแปลงอะไร แต่ยังตรวจ EROFS ให้
+                fs::rename(path, path)?;
+                check_unlink(path)
+            }
+            AccessMode::Delete => check_unlink(path),
+            AccessMode::Exec => {
+                access(path, libc::X_OK)?;
+                check_noexec(path)
+            }
+        }
+    }
+
+    pub fn classify(error: &io::Error) -> Option<DenialClass> {
+        let class = match error.raw_os_error()? {
+            libc::EACCES => DenialClass::PermissionDenied,
+            libc::EBUSY => DenialClass::Busy,
+            libc::ETXTBSY => DenialClass::TextBusy,
+            libc::EROFS => DenialClass::ReadOnlyFs,
+            libc::EPERM => DenialClass::NotPermitted,
+            libc::EWOULDBLOCK => DenialClass::WouldBlock,
+            _ => return None,
+        };
+        Some(class)
+    }
+
+    // เปิดไฟล์เพื่ออ่านแบบ non-blocking เพื่อให้ write lease คืน EWOULDBLOCK แทนการรอ
+    fn open_read(path: &Path) -> io::Result<()> {
+        OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK | libc::O_NOCTTY)
+            .open(path)
+            .map(|_| ())
+    }
+
+    // ตรวจการเขียนโดยไม่เปิดไฟล์จริง เพราะการเปิดเพื่อเขียนจะ break lease ของ process อื่น
+    // และการปิด fd จะส่ง IN_CLOSE_WRITE ให้ inotify watcher
+    // access() ให้ EACCES, EROFS และ EPERM ของไฟล์ immutable ส่วน lease และ mandatory lock ดูจาก /proc/locks
+    fn check_write(path: &Path, append: bool) -> io::Result<()> {
+        access(path, libc::W_OK)?;
+
+        // ไฟล์ append-only เปิดเขียนได้เฉพาะแบบ O_APPEND
+        if !append && inode_flags(path).is_ok_and(|flags| flags & FS_APPEND_FL != 0) {
+            return Err(io::Error::from_raw_os_error(libc::EPERM));
+        }
+
+        // lease ทุกแบบขวางการเปิดเพื่อเขียน เช่นเดียวกับ mandatory lock ของ process ใดก็ได้
+        let metadata = fs::metadata(path)?;
+        let blocked = read_proc_locks().unwrap_or_default().iter().any(|lock| {
+            (lock.dev, lock.ino) == (metadata.dev(), metadata.ino())
+                && !lock.waiting
+                && (lock.record.class == LockClass::Lease || lock.record.mandatory)
+        });
+        if blocked {
+            return Err(io::Error::from_raw_os_error(libc::EWOULDBLOCK));
+        }
+        Ok(())
+    }
+
+    // ตรวจสิทธิ์ด้วย effective uid เหมือนกับการเปิดไฟล์จริง
+    pub fn access(path: &Path, mode: libc::c_int) -> io::Result<()> {
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let result =
+            unsafe { libc::faccessat(libc::AT_FDCWD, c_path.as_ptr(), mode, libc::AT_EACCESS) };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    // อ่าน attribute immutable / append-only ของ inode ด้วย statx
+    // ไม่เปิดไฟล์ จึงไม่ติด lease และไม่ break lease ของ process อื่น
+    pub fn inode_flags(path: &Path) -> io::Result<u64> {
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let mut stat: libc::statx = unsafe { std::mem::zeroed() };
+        let result = unsafe { libc::statx(libc::AT_FDCWD, c_path.as_ptr(), 0, 0, &mut stat) };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(stat.stx_attributes & stat.stx_attributes_mask)
+    }
+
+    // เงื่อนไขที่ทำให้ unlink หรือ rename ไม่ได้ โดยไม่ลบไฟล์จริง
+    fn check_unlink(path: &Path) -> io::Result<()> {
+        let metadata = fs::symlink_metadata(path)?;
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let parent_metadata = fs::metadata(parent)?;
+
+        // mount point ลบหรือย้ายไม่ได้
+        if metadata.dev() != parent_metadata.dev() {
+            return Err(io::Error::from_raw_os_error(libc::EBUSY));
+        }
+
+        access(parent, libc::W_OK | libc::X_OK)?;
+
+        // sticky bit: ต้องเป็นเจ้าของไฟล์ เจ้าของ directory หรือ root
+        let euid = unsafe { libc::geteuid() };
+        if parent_metadata.mode() & libc::S_ISVTX != 0
+            && euid != 0
+            && euid != metadata.uid()
+            && euid != parent_metadata.uid()
+        {
+            return Err(io::Error::from_raw_os_error(libc::EPERM));
+        }
+
+        // ไฟล์ที่เป็น immutable หรือ append-only ลบหรือย้ายไม่ได้
+        if !metadata.file_type().is_symlink()
+            && let Ok(flags) = inode_flags(path)
+            && flags & (FS_IMMUTABLE_FL | FS_APPEND_FL) != 0
+        {
+            return Err(io::Error::from_raw_os_error(libc::EPERM));
+        }
+
+        Ok(())
+    }
+
+    // filesystem ที่ mount แบบ noexec จะรันไฟล์ไม่ได้แม้มี x bit
+    fn check_noexec(path: &Path) -> io::Result<()> {
+        if mount_flags(path)? & libc::ST_NOEXEC != 0 {
+            return Err(io::Error::from_raw_os_error(libc::EACCES));
+        }
+        Ok(())
+    }
+
+    // อ่าน flag ของ filesystem ที่ไฟล์อยู่ เช่น ST_RDONLY และ ST_NOEXEC
+    pub fn mount_flags(path: &Path) -> io::Result<libc::c_ulong> {
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(stat.f_flag)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{AccessMode, DenialClass};
+    use std::fs::OpenOptions;
+    use std::io;
+    use std::os::windows::fs::OpenOptionsExt;
+    use winapi::shared::winerror::{
+        ERROR_ACCESS_DENIED, ERROR_LOCK_VIOLATION, ERROR_SHARING_VIOLATION, ERROR_WRITE_PROTECT,
+    };
+    use winapi::um::winbase::FILE_FLAG_BACKUP_SEMANTICS;
+    use winapi::um::winnt::{
+        DELETE, FILE_APPEND_DATA, FILE_EXECUTE, FILE_GENERIC_READ, FILE_SHARE_DELETE,
+        FILE_SHARE_READ, FILE_SHARE_WRITE, FILE_WRITE_DATA,
+    };
+
+    // เปิด handle ด้วยสิทธิ์ที่ตรงกับการเข้าถึงแต่ละแบบ โดยอนุญาตให้ผู้อื่นแชร์ได้ทั้งหมด
+    pub fn probe(file_path: &str, mode: AccessMode) -> io::Result<()> {
+        let access = match mode {
+            AccessMode::Read => FILE_GENERIC_READ,
+            AccessMode::Write => FILE_WRITE_DATA,
+            AccessMode::Append => FILE_APPEND_DATA,
+            AccessMode::Rename | AccessMode::Delete => DELETE,
+            AccessMode::Exec => FILE_EXECUTE,
+        };
+
+        OpenOptions::new()
+            .access_mode(access)
+            .share_mode(FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE)
+            .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+            .open(file_path)
+            .map(|_| ())
+    }
+
+    pub fn classify(error: &io::Error) -> Option<DenialClass> {
+        let class = match error.raw_os_error()? as u32 {
+            ERROR_ACCESS_DENIED => DenialClass::PermissionDenied,
+            ERROR_SHARING_VIOLATION => DenialClass::Busy,
+            ERROR_LOCK_VIOLATION => DenialClass::WouldBlock,
+            ERROR_WRITE_PROTECT => DenialClass::ReadOnlyFs,
+            _ => return None,
+        };
+        Some(class)
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+mod platform {
+    use super::{AccessMode, DenialClass};
+    use std::fs::{self, OpenOptions};
+    use std::io;
+
+    pub fn probe(file_path: &str, mode: AccessMode) -> io::Result<()> {
+        match mode {
+            AccessMode::Read => OpenOptions::new().read(true).open(file_path).map(|_| ()),
+            AccessMode::Write => OpenOptions::new().write(true).open(file_path).map(|_| ()),
+            AccessMode::Append => OpenOptions::new().append(true).open(file_path).map(|_| ()),
+            AccessMode::Rename => fs::rename(file_path, file_path),
+            AccessMode::Delete | AccessMode::Exec => fs::metadata(file_path).map(|_| ()),
+        }
+    }
+
+    pub fn classify(_error: &io::Error) -> Option<DenialClass> {
+        None
+    }
+}
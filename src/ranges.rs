@@ -0,0 +1,263 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use file_lock_checker::{FileLockInfo, LockClass, LockMode, LockRecord};
+
+// ความกว้างของแถบ ASCII
+const BAR_WIDTH: usize = 60;
+
+// lock หนึ่งรายการพร้อมเจ้าของ
+// OFD lock ผูกกับ open file description ซึ่งหลาย process ใช้ร่วมกันได้ (เช่นหลัง fork)
+// จึงมีได้หลายเจ้าของ แต่ยังเป็น lock เดียว
+struct RangeEntry<'a> {
+    owners: Vec<(u32, &'a str)>,
+    record: &'a LockRecord,
+}
+
+impl RangeEntry<'_> {
+    fn pids(&self) -> String {
+        let pids: Vec<String> = self.owners.iter().map(|(pid, _)| pid.to_string()).collect();
+        pids.join(",")
+    }
+
+    fn names(&self) -> String {
+        let mut names: Vec<&str> = self.owners.iter().map(|(_, name)| *name).collect();
+        names.dedup();
+        names.join(", ")
+    }
+
+    // lock สองรายการขัดแย้งกันเมื่อเป็นของต่างเจ้าของ และอย่างน้อยหนึ่งรายการเป็น WRITE
+    // flock ไม่ขัดแย้งกับ POSIX/OFD lock บน Linux
+    fn conflicts_with(&self, other: &RangeEntry) -> bool {
+        let flock = |entry: &RangeEntry| entry.record.class == LockClass::Flock;
+        (self.record.mode == LockMode::Write || other.record.mode == LockMode::Write)
+            && flock(self) == flock(other)
+            && !self.owners.iter().any(|owner| other.owners.contains(owner))
+    }
+}
+
+// รวม lock ของทุก process เป็นรายการ lock ที่ไม่ซ้ำกัน
+// OFD lock ที่เหมือนกันทุกอย่างคือ lock เดียวกันที่เห็นจากหลาย process
+fn range_entries(lock_info: &FileLockInfo) -> Vec<RangeEntry<'_>> {
+    let mut entries: Vec<RangeEntry> = Vec::new();
+    for process in &lock_info.processes {
+        for record in &process.locks {
+            let owner = (process.pid, process.name.as_str());
+            match entries
+                .iter_mut()
+                .find(|entry| record.class == LockClass::OfdLck && entry.record == record)
+            {
+                Some(entry) => entry.owners.push(owner),
+                None => entries.push(RangeEntry {
+                    owners: vec![owner],
+                    record,
+                }),
+            }
+        }
+    }
+    entries
+}
+
+// แสดง range map ของ lock ทั้งหมดบนไฟล์ พร้อมช่วงที่ซ้อนกันและแถบ ASCII
+pub fn print_range_map(lock_info: &FileLockInfo, file_size: u64) {
+    let mut entries = range_entries(lock_info);
+
+    if entries.is_empty() {
+        println!("File '{}' has no byte-range locks", lock_info.file_path);
+        return;
+    }
+
+    entries.sort_by_key(|entry| (entry.record.start, entry.record.end, entry.owners[0].0));
+
+    // ขอบเขตของแถบ: ขนาดไฟล์ หรือ byte สุดท้ายที่ถูก lock ถ้าเกินขนาดไฟล์
+    let extent = entries
+        .iter()
+        .map(|entry| {
+            entry
+                .record
+                .end
+                .unwrap_or(entry.record.start)
+                .saturating_add(1)
+        })
+        .max()
+        .unwrap_or(0)
+        .max(file_size)
+        .max(1);
+
+    println!(
+        "Byte-range locks on '{}' ({} bytes):",
+        lock_info.file_path, file_size
+    );
+    println!("{:-<80}", "");
+    println!(
+        "{:<12} {:<12} {:<8} {:<6} Name",
+        "Start", "End", "PID", "Mode"
+    );
+    for entry in &entries {
+        println!(
+            "{:<12} {:<12} {:<8} {:<6} {}",
+            entry.record.start,
+            format_end(entry.record.end),
+            entry.pids(),
+            entry.record.mode,
+            entry.names()
+        );
+    }
+    println!();
+
+    print_overlaps(&entries, extent);
+    print_bars(&entries, extent);
+}
+
+// แสดงช่วงที่มีหลาย process ถือ lock ซ้อนกัน
+fn print_overlaps(entries: &[RangeEntry], extent: u64) {
+    // แบ่งไฟล์เป็นช่วงย่อยตามจุดเริ่มต้นและจุดสิ้นสุดของทุก lock
+    let mut bounds: Vec<u64> = entries
+        .iter()
+        .flat_map(|entry| [entry.record.start, end_exclusive(entry.record, extent)])
+        .collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut found = false;
+    for window in bounds.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        let holders: Vec<&RangeEntry> = entries
+            .iter()
+            .filter(|entry| {
+                entry.record.start <= start && end_exclusive(entry.record, extent) >= end
+            })
+            .collect();
+        if holders.len() < 2 {
+            continue;
+        }
+
+        if !found {
+            println!("Overlapping regions:");
+            found = true;
+        }
+
+        // lock ไม่ขัดแย้งกับตัวเอง จึงเทียบเฉพาะคู่ของ lock ที่ต่างกัน
+        let conflict = holders.iter().enumerate().any(|(i, entry)| {
+            holders[i + 1..]
+                .iter()
+                .any(|other| entry.conflicts_with(other))
+        });
+        let owners: Vec<String> = holders
+            .iter()
+            .map(|entry| format!("{} {}", entry.pids(), entry.record.mode))
+            .collect();
+        println!(
+            "  {}-{}: {}{}",
+            start,
+            end - 1,
+            owners.join(", "),
+            if conflict { " (conflict)" } else { "" }
+        );
+    }
+
+    if found {
+        println!();
+    }
+}
+
+// แสดงแถบ ASCII หนึ่งแถวต่อ lock: R = READ, W = WRITE
+fn print_bars(entries: &[RangeEntry], extent: u64) {
+    println!("{:<17}0{:>width$}", "", extent - 1, width = BAR_WIDTH - 1);
+    for entry in entries {
+        let first = scale(entry.record.start, extent);
+        let last = scale(end_exclusive(entry.record, extent) - 1, extent);
+        let mark = match entry.record.mode {
+            LockMode::Read => 'R',
+            LockMode::Write => 'W',
+        };
+        let bar: String = (0..BAR_WIDTH)
+            .map(|i| if i >= first && i <= last { mark } else { '.' })
+            .collect();
+        println!("{:<16} |{}|", format!("PID {}", entry.pids()), bar);
+    }
+}
+
+// แปลงตำแหน่ง byte เป็นตำแหน่งบนแถบ
+fn scale(offset: u64, extent: u64) -> usize {
+    ((offset as u128 * BAR_WIDTH as u128) / extent as u128).min(BAR_WIDTH as u128 - 1) as usize
+}
+
+// ตำแหน่งสิ้นสุดแบบ exclusive โดยให้ EOF สิ้นสุดที่ extent
+fn end_exclusive(record: &LockRecord, extent: u64) -> u64 {
+    match record.end {
+        Some(end) => end.saturating_add(1),
+        None => extent.max(record.start + 1),
+    }
+}
+
+fn format_end(end: Option<u64>) -> String {
+    match end {
+        Some(end) => end.to_string(),
+        None => "EOF".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use file_lock_checker::ProcessInfo;
+
+    fn record(class: LockClass, mode: LockMode, start: u64, end: u64) -> LockRecord {
+        LockRecord {
+            class,
+            mode,
+            mandatory: false,
+            start,
+            end: Some(end),
+        }
+    }
+
+    fn process(pid: u32, locks: Vec<LockRecord>) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            locks,
+            ..Default::default()
+        }
+    }
+
+    fn lock_info(processes: Vec<ProcessInfo>) -> FileLockInfo {
+        FileLockInfo {
+            processes,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn shared_ofd_lock_is_one_entry_without_conflict() {
+        let ofd = record(LockClass::OfdLck, LockMode::Write, 0, 3);
+        let info = lock_info(vec![process(10, vec![ofd.clone()]), process(11, vec![ofd])]);
+        let entries = range_entries(&info);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].pids(), "10,11");
+        assert!(!entries[0].conflicts_with(&entries[0]));
+    }
+
+    #[test]
+    fn overlapping_writes_of_different_processes_conflict() {
+        let info = lock_info(vec![
+            process(10, vec![record(LockClass::Posix, LockMode::Write, 0, 9)]),
+            process(11, vec![record(LockClass::Posix, LockMode::Read, 5, 15)]),
+        ]);
+        let entries = range_entries(&info);
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].conflicts_with(&entries[1]));
+    }
+
+    #[test]
+    fn reads_and_flock_against_posix_do_not_conflict() {
+        let info = lock_info(vec![
+            process(10, vec![record(LockClass::Posix, LockMode::Read, 0, 9)]),
+            process(11, vec![record(LockClass::Posix, LockMode::Read, 0, 9)]),
+            process(12, vec![record(LockClass::Flock, LockMode::Write, 0, 9)]),
+        ]);
+        let entries = range_entries(&info);
+        assert!(!entries[0].conflicts_with(&entries[1]));
+        assert!(!entries[0].conflicts_with(&entries[2]));
+    }
+}
@@ -1,13 +1,14 @@
 // Copyright (c) 2025 Phumin Maliwan
 // SPDX-License-Identifier: MIT
 
+use file_lock_checker::FlcError;
 use std::ffi::OsString;
 use std::os::windows::ffi::OsStrExt;
 use std::ptr;
 use winapi::shared::minwindef::{DWORD, HKEY};
 use winapi::um::winnt::{KEY_WRITE, REG_SZ};
 use winapi::um::winreg::{
-    RegCloseKey, RegCreateKeyExW, RegDeleteTreeW, RegSetValueExW, HKEY_CLASSES_ROOT,
+    HKEY_CLASSES_ROOT, RegCloseKey, RegCreateKeyExW, RegDeleteTreeW, RegSetValueExW,
 };
 
 // สร้าง registry
@@ -17,7 +18,7 @@ pub fn create_registry_key(
     display_name: &str,
     exe_path: &str,
     command: &str,
-) -> Result<(), String> {
+) -> Result<(), FlcError> {
     unsafe {
         // สร้าง key หลัก
         let menu_key_path = format!("{}\\{}", base_key, key_name);
@@ -41,7 +42,10 @@ pub fn create_registry_key(
         );
 
         if result != 0 {
-            return Err(format!("Failed to create registry key: {}", result));
+            return Err(FlcError::Registry {
+                action: format!("Failed to create registry key '{}'", menu_key_path),
+                code: result,
+            });
         }
 
         // ตั้งค่า display name
@@ -75,7 +79,10 @@ pub fn create_registry_key(
         );
 
         if result != 0 {
-            return Err(format!("Failed to create command key: {}", result));
+            return Err(FlcError::Registry {
+                action: format!("Failed to create command key '{}'", command_key_path),
+                code: result,
+            });
         }
 
         // ตั้งค่า command
@@ -89,7 +96,7 @@ pub fn create_registry_key(
 }
 
 // ตั้งค่า registry value
-fn set_registry_value(key: HKEY, name: &str, value: &str) -> Result<(), String> {
+fn set_registry_value(key: HKEY, name: &str, value: &str) -> Result<(), FlcError> {
     unsafe {
         let wide_name: Vec<u16> = if name.is_empty() {
             vec![0]
@@ -115,7 +122,10 @@ fn set_registry_value(key: HKEY, name: &str, value: &str) -> Result<(), String>
         );
 
         if result != 0 {
-            return Err(format!("Failed to set registry value: {}", result));
+            return Err(FlcError::Registry {
+                action: format!("Failed to set registry value '{}'", name),
+                code: result,
+            });
         }
     }
 
@@ -123,7 +133,7 @@ fn set_registry_value(key: HKEY, name: &str, value: &str) -> Result<(), String>
 }
 
 // ลบ registry key
-pub fn delete_registry_key(key_path: &str) -> Result<(), String> {
+pub fn delete_registry_key(key_path: &str) -> Result<(), FlcError> {
     unsafe {
         let wide_key: Vec<u16> = OsString::from(key_path)
             .encode_wide()
@@ -140,4 +150,4 @@ pub fn delete_registry_key(key_path: &str) -> Result<(), String> {
     }
 
     Ok(())
-}
\ No newline at end of file
+}
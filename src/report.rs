@@ -0,0 +1,221 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use crate::error::FlcError;
+use crate::types::{DeletedFiles, FileLockInfo, ProcessInfo};
+use serde::Serialize;
+
+// version ของรูปแบบ JSON จะเพิ่มขึ้นเมื่อมีการเปลี่ยนแปลงที่ไม่ backward compatible
+pub const SCHEMA_VERSION: u32 = 1;
+
+// JSON Schema ของทุก document ใน version นี้
+pub const SCHEMA: &str = include_str!("../schema/file-lock-checker.v1.schema.json");
+
+// document ระดับบนสุดที่ออกทาง --format json
+#[derive(Debug, Serialize)]
+pub struct Document<'a> {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub report: Report<'a>,
+}
+
+impl<'a> Document<'a> {
+    pub fn new(report: Report<'a>) -> Self {
+        Document {
+            schema_version: SCHEMA_VERSION,
+            report,
+        }
+    }
+}
+
+// เนื้อหาของ document แยกตาม "kind"
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Report<'a> {
+    // ผลของคำสั่ง check
+    Check(&'a FileLockInfo),
+    // ผลของคำสั่ง check หลายไฟล์ หนึ่งรายการต่อไฟล์ตามลำดับที่ระบุ
+    CheckMany {
+        files: Vec<FileResult<'a>>,
+    },
+    // ผลของคำสั่ง unlock
+    Unlock(&'a UnlockReport),
+    // สถานะ lock ที่เปลี่ยนไประหว่าง monitor
+    MonitorChange(&'a MonitorChange),
+    // event หนึ่งรายการใน stream ของ monitor --format ndjson
+    MonitorEvent(&'a MonitorEvent),
+    // ผลของคำสั่ง deleted
+    Deleted(&'a DeletedFiles),
+    // คำสั่งทำงานไม่สำเร็จ
+    Error {
+        file_path: Option<&'a str>,
+        error: &'a FlcError,
+    },
+}
+
+// ผลของไฟล์หนึ่งไฟล์ใน check_many แยกกันได้จาก field "error"
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum FileResult<'a> {
+    Checked(&'a FileLockInfo),
+    Failed {
+        file_path: &'a str,
+        error: &'a FlcError,
+    },
+}
+
+// ผลการ kill process หนึ่งตัว
+#[derive(Debug, Serialize)]
+pub struct KillOutcome {
+    pub pid: u32,
+    pub name: String,
+    // None ถ้า kill สำเร็จ
+    pub error: Option<FlcError>,
+}
+
+// ผลของคำสั่ง unlock ทั้งก่อนและหลัง kill
+#[derive(Debug, Serialize)]
+pub struct UnlockReport {
+    pub file_path: String,
+    pub before: FileLockInfo,
+    // ผู้ใช้ตอบ no หรือไม่มี process ให้ kill
+    pub cancelled: bool,
+    pub killed: Vec<KillOutcome>,
+    // ผลการตรวจซ้ำหลัง kill (None ถ้าไม่ได้ kill)
+    pub after: Option<FileLockInfo>,
+}
+
+// การเปลี่ยนแปลงหนึ่งครั้งที่ monitor ตรวจพบ
+#[derive(Debug, Serialize)]
+pub struct MonitorChange {
+    // เวลาแบบ RFC 3339
+    pub timestamp: String,
+    pub file_path: String,
+    pub locked: bool,
+    pub processes: Vec<ProcessInfo>,
+}
+
+impl MonitorChange {
+    pub fn new(file_path: &str, processes: &[ProcessInfo]) -> Self {
+        MonitorChange {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            file_path: file_path.to_string(),
+            locked: !processes.is_empty(),
+            processes: processes.to_vec(),
+        }
+    }
+}
+
+// ประเภทของ event ใน stream ของ monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorEventKind {
+    // ไฟล์เปลี่ยนจากไม่มีผู้ถือเป็นมีผู้ถือ
+    Locked,
+    // ผู้ถือคนสุดท้ายปล่อยไฟล์
+    Unlocked,
+    HolderAdded,
+    HolderRemoved,
+}
+
+// event หนึ่งรายการ แต่ละรายการมี process ที่เกี่ยวข้องหนึ่งตัว
+#[derive(Debug, Serialize)]
+pub struct MonitorEvent {
+    // เวลาแบบ RFC 3339
+    pub timestamp: String,
+    pub file_path: String,
+    pub event: MonitorEventKind,
+    pub process: ProcessInfo,
+}
+
+// แปลงการเปลี่ยนแปลงของผู้ถือไฟล์ระหว่างสองรอบเป็น event
+//
+// process ที่หายไปได้ holder_removed ยกเว้นตัวสุดท้ายที่ทำให้ไฟล์ว่างซึ่งได้ unlocked
+// process ใหม่ได้ holder_added ยกเว้นตัวแรกที่ทำให้ไฟล์ถูก lock ซึ่งได้ locked
+// ดังนั้นทุก process ที่เปลี่ยนจะปรากฏเพียงครั้งเดียว
+pub fn monitor_events(
+    file_path: &str,
+    old_processes: &[ProcessInfo],
+    new_processes: &[ProcessInfo],
+) -> Vec<MonitorEvent> {
+    let timestamp = chrono::Local::now().to_rfc3339();
+    let contains = |processes: &[ProcessInfo], pid: u32| processes.iter().any(|p| p.pid == pid);
+
+    let removed: Vec<&ProcessInfo> = old_processes
+        .iter()
+        .filter(|p| !contains(new_processes, p.pid))
+        .collect();
+    let added: Vec<&ProcessInfo> = new_processes
+        .iter()
+        .filter(|p| !contains(old_processes, p.pid))
+        .collect();
+
+    let mut events = Vec::new();
+    let mut push = |event, process: &ProcessInfo| {
+        events.push(MonitorEvent {
+            timestamp: timestamp.clone(),
+            file_path: file_path.to_string(),
+            event,
+            process: process.clone(),
+        });
+    };
+
+    for (i, process) in removed.iter().enumerate() {
+        let last = i + 1 == removed.len();
+        if last && new_processes.is_empty() {
+            push(MonitorEventKind::Unlocked, process);
+        } else {
+            push(MonitorEventKind::HolderRemoved, process);
+        }
+    }
+    for (i, process) in added.iter().enumerate() {
+        if i == 0 && old_processes.is_empty() {
+            push(MonitorEventKind::Locked, process);
+        } else {
+            push(MonitorEventKind::HolderAdded, process);
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(old: &[u32], new: &[u32]) -> Vec<(MonitorEventKind, u32)> {
+        let processes = |pids: &[u32]| -> Vec<ProcessInfo> {
+            pids.iter()
+                .map(|&pid| ProcessInfo {
+                    pid,
+                    ..Default::default()
+                })
+                .collect()
+        };
+        let (old, new) = (processes(old), processes(new));
+        monitor_events("data.db", &old, &new)
+            .into_iter()
+            .map(|event| (event.event, event.process.pid))
+            .collect()
+    }
+
+    #[test]
+    fn first_holder_locks_and_others_are_added() {
+        use MonitorEventKind::*;
+        assert_eq!(events(&[], &[1, 2]), [(Locked, 1), (HolderAdded, 2)]);
+        assert_eq!(events(&[1], &[1, 2]), [(HolderAdded, 2)]);
+    }
+
+    #[test]
+    fn last_holder_unlocks_and_others_are_removed() {
+        use MonitorEventKind::*;
+        assert_eq!(events(&[1, 2], &[]), [(HolderRemoved, 1), (Unlocked, 2)]);
+        assert_eq!(events(&[1, 2], &[2]), [(HolderRemoved, 1)]);
+    }
+
+    #[test]
+    fn unchanged_holders_have_no_events() {
+        assert!(events(&[1, 2], &[2, 1]).is_empty());
+        assert!(events(&[], &[]).is_empty());
+    }
+}
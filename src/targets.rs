@@ -0,0 +1,208 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+// รวบรวม path ที่จะตรวจจาก argument และ stdin
+//
+//   path        ใช้ตามที่ระบุ
+//   @listfile   อ่าน path จากไฟล์ บรรทัดละหนึ่ง path โดยข้ามบรรทัดว่าง
+//   --null      อ่าน path จาก stdin ที่คั่นด้วย NUL เช่นจาก find -print0
+//   pattern     glob เช่น **/*.dll หรือ logs/*.log แทนด้วย path ที่ match ก่อนตรวจ
+//
+// path ที่ขึ้นต้นด้วย @ จริง ๆ ให้เขียนเป็น ./@name
+
+use glob::{MatchOptions, Pattern};
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+// directory ที่อ่านไม่ได้ระหว่างหา path พร้อมสาเหตุ
+pub type Skipped = Vec<(String, io::Error)>;
+
+// ชื่อไฟล์บน Windows ไม่แยกตัวพิมพ์เล็กใหญ่ จึง match แบบเดียวกัน
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: !cfg!(windows),
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+// path ทั้งหมดตามลำดับที่ระบุ
+pub fn collect(args: &[String], null_stdin: bool) -> Result<Vec<String>, String> {
+    let mut paths = Vec::new();
+    for arg in args {
+        match arg.strip_prefix('@') {
+            Some(list) => {
+                let content = fs::read_to_string(list)
+                    .map_err(|e| format!("Failed to read list file '{}': {}", list, e))?;
+                paths.extend(
+                    content
+                        .lines()
+                        .map(|line| line.trim_end_matches('\r'))
+                        .filter(|line| !line.is_empty())
+                        .map(str::to_string),
+                );
+            }
+            None => paths.push(arg.clone()),
+        }
+    }
+
+    if null_stdin {
+        let mut input = Vec::new();
+        io::stdin()
+            .read_to_end(&mut input)
+            .map_err(|e| format!("Failed to read paths from stdin: {}", e))?;
+        paths.extend(
+            input
+                .split(|byte| *byte == 0)
+                .filter(|path| !path.is_empty())
+                .map(|path| String::from_utf8_lossy(path).into_owned()),
+        );
+    }
+
+    Ok(paths)
+}
+
+// ใช้ผลแบบหลายไฟล์เมื่อ path มาจาก list หรือ stdin แม้จะได้แค่ไฟล์เดียว
+// เพื่อให้ script ได้รูปแบบเดิมทุกครั้ง
+pub fn is_many(args: &[String], null_stdin: bool) -> bool {
+    null_stdin
+        || args.len() != 1
+        || args
+            .iter()
+            .any(|arg| arg.starts_with('@') || is_pattern(arg))
+}
+
+// argument ที่มีอักขระ glob ถือเป็น pattern เว้นแต่มี path ชื่อนั้นอยู่จริง (เช่น 'report [1].txt')
+pub fn is_pattern(arg: &str) -> bool {
+    arg.contains(['*', '?', '[']) && fs::symlink_metadata(arg).is_err()
+}
+
+// แทน pattern ด้วย path ที่ match ตามลำดับของ filesystem ส่วน path อื่นใช้ตามเดิม
+// pattern ที่ไม่ match อะไรเลยถือเป็น error เพราะมักพิมพ์ผิด
+// directory ที่อ่านไม่ได้ระหว่าง match จะถูกข้ามและคืนมาใน skipped
+pub fn expand(args: &[String]) -> Result<(Vec<String>, Skipped), String> {
+    let mut paths = Vec::new();
+    let mut skipped = Vec::new();
+    for arg in args {
+        if !is_pattern(arg) {
+            paths.push(arg.clone());
+            continue;
+        }
+
+        let entries = glob::glob_with(arg, MATCH_OPTIONS)
+            .map_err(|e| format!("Invalid pattern '{}': {}", arg, e))?;
+        let before = paths.len();
+        for entry in entries {
+            match entry {
+                Ok(path) => paths.push(path.to_string_lossy().into_owned()),
+                Err(e) => {
+                    let dir = e.path().to_string_lossy().into_owned();
+                    skipped.push((dir, e.into()));
+                }
+            }
+        }
+        if paths.len() == before {
+            return Err(format!("No paths match '{}'", arg));
+        }
+    }
+    Ok((paths, skipped))
+}
+
+// ตัด path ที่ตรงกับ --exclude ออก รวมถึงทุกอย่างที่อยู่ใต้ directory ที่ตรงกัน
+// pattern ที่ไม่มีตัวคั่น path เช่น *.log เทียบกับชื่อแต่ละส่วน ส่วน pattern อื่นเทียบกับ path ทั้งหมด
+pub fn exclude(paths: &mut Vec<String>, patterns: &[Pattern]) {
+    if patterns.is_empty() {
+        return;
+    }
+    paths.retain(|path| {
+        !Path::new(path).ancestors().any(|ancestor| {
+            let ancestor = ancestor.strip_prefix(".").unwrap_or(ancestor);
+            patterns.iter().any(|pattern| {
+                if pattern.as_str().contains(['/', std::path::MAIN_SEPARATOR]) {
+                    pattern.matches_path_with(ancestor, MATCH_OPTIONS)
+                } else {
+                    ancestor.file_name().is_some_and(|name| {
+                        pattern.matches_with(&name.to_string_lossy(), MATCH_OPTIONS)
+                    })
+                }
+            })
+        })
+    });
+}
+
+// path ทั้งหมดใต้ directory รวมตัว directory เองและ directory ย่อย (เพื่อหา process ที่มี cwd อยู่ในนั้น)
+// ไม่ตามและไม่นับ symlink เพื่อไม่ให้วนซ้ำหรือออกนอก tree
+// directory ที่อ่านไม่ได้จะถูกข้ามและคืนมาใน skipped
+pub fn walk(root: &str) -> (Vec<String>, Skipped) {
+    let mut paths = vec![root.to_string()];
+    let mut skipped = Vec::new();
+    let mut pending = vec![Path::new(root).to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                // root ที่เป็นไฟล์ไม่ต้องเดินต่อ
+                if dir != Path::new(root) || fs::metadata(root).is_ok_and(|m| m.is_dir()) {
+                    skipped.push((dir.to_string_lossy().into_owned(), e));
+                }
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            // symlink อาจชี้ออกนอก directory การตรวจผ่าน link จะรายงาน holder ของไฟล์อื่น
+            if file_type.is_symlink() {
+                continue;
+            }
+            let path = entry.path();
+            if file_type.is_dir() {
+                pending.push(path.clone());
+            }
+            paths.push(path.to_string_lossy().into_owned());
+        }
+    }
+
+    paths.sort();
+    (paths, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn excluded(paths: &[&str], patterns: &[&str]) -> Vec<String> {
+        let mut paths: Vec<String> = paths.iter().map(|path| path.to_string()).collect();
+        let patterns: Vec<Pattern> = patterns
+            .iter()
+            .map(|pattern| Pattern::new(pattern).unwrap())
+            .collect();
+        exclude(&mut paths, &patterns);
+        paths
+    }
+
+    #[test]
+    fn name_pattern_matches_any_component() {
+        let paths = ["app/logs/a.txt", "app/src/main.rs", "app/x.log", "logs"];
+        assert_eq!(
+            excluded(&paths, &["logs"]),
+            ["app/src/main.rs", "app/x.log"]
+        );
+        assert_eq!(
+            excluded(&paths, &["*.log"]),
+            ["app/logs/a.txt", "app/src/main.rs", "logs"]
+        );
+    }
+
+    #[test]
+    fn path_pattern_matches_whole_path_and_everything_below() {
+        let paths = ["app/logs/a.txt", "other/app/logs/b.txt", "./app/logs"];
+        assert_eq!(excluded(&paths, &["app/logs"]), ["other/app/logs/b.txt"]);
+    }
+
+    #[test]
+    fn no_patterns_keep_every_path() {
+        assert_eq!(excluded(&["a", "b"], &[]), ["a", "b"]);
+    }
+}
@@ -0,0 +1,420 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+// template ที่ผู้ใช้กำหนดเองผ่าน --template
+//
+//   {field}                 แทนที่ด้วยค่าของ field
+//   {#holders}...{/holders} ทำซ้ำหนึ่งครั้งต่อ process ที่เปิดไฟล์
+//   {?field}...{/field}     แสดงเมื่อ field ไม่ว่าง (holders/waiters คือมีอย่างน้อยหนึ่งตัว)
+//   {^field}...{/field}     แสดงเมื่อ field ว่าง เช่น {^holders}not locked{/holders}
+//   \t \n \\ {{ }}          tab, ขึ้นบรรทัดใหม่, backslash และวงเล็บปีกกา
+//
+// ถ้าไม่มี {#holders} แต่ใช้ field ของ process เช่น {pid} ทั้ง template จะถูกทำซ้ำ
+// หนึ่งครั้งต่อ process และไม่แสดงอะไรสำหรับไฟล์ที่ไม่มีใครเปิด เว้นแต่มี {^holders}
+// ซึ่งจะแสดงหนึ่งครั้งโดยให้ field ของ process ว่าง
+
+use file_lock_checker::{FileLockInfo, ProcessInfo};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    // field ของไฟล์
+    File,
+    Holders,
+    Waiters,
+    // field ของ process แต่ละตัว
+    Index,
+    Pid,
+    Name,
+    Path,
+    User,
+    Mode,
+    Locks,
+    Opened,
+}
+
+const FIELD_NAMES: &str =
+    "file, holders, waiters, index, pid, name, path, user, mode, locks, opened";
+
+impl Field {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "file" => Ok(Field::File),
+            "holders" => Ok(Field::Holders),
+            "waiters" => Ok(Field::Waiters),
+            "index" => Ok(Field::Index),
+            "pid" => Ok(Field::Pid),
+            "name" => Ok(Field::Name),
+            "path" => Ok(Field::Path),
+            "user" => Ok(Field::User),
+            "mode" => Ok(Field::Mode),
+            "locks" => Ok(Field::Locks),
+            "opened" => Ok(Field::Opened),
+            _ => Err(format!(
+                "Unknown template field '{{{}}}' (expected {})",
+                name, FIELD_NAMES
+            )),
+        }
+    }
+
+    fn per_holder(self) -> bool {
+        !matches!(self, Field::File | Field::Holders | Field::Waiters)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Field(Field),
+    // {#holders}...{/holders}
+    EachHolder(Vec<Node>),
+    // {?field}...{/field} หรือ {^field}...{/field} เมื่อ negate
+    If {
+        field: Field,
+        negate: bool,
+        body: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+    // ทำซ้ำทั้ง template หนึ่งครั้งต่อ process
+    per_holder: bool,
+    // มี {^holders} จึงต้องแสดงแม้ไม่มี process
+    when_free: bool,
+}
+
+// process ที่กำลังแสดงอยู่พร้อมลำดับ (เริ่มที่ 1)
+type Holder<'a> = Option<(usize, &'a ProcessInfo)>;
+
+impl std::str::FromStr for Template {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            chars: s.chars().collect(),
+            pos: 0,
+        };
+        let nodes = parser.parse_nodes(None)?;
+
+        let has_each = has_each(&nodes);
+        let uses_holder_field = outside_each(&nodes);
+        if has_each && uses_holder_field {
+            return Err(
+                "Process fields such as {pid} can only be used inside {#holders}...{/holders}"
+                    .to_string(),
+            );
+        }
+
+        Ok(Template {
+            when_free: has_free_section(&nodes),
+            nodes,
+            per_holder: uses_holder_field,
+        })
+    }
+}
+
+impl Template {
+    // แสดงผลของไฟล์หนึ่งไฟล์ ทุกครั้งที่แสดงจะจบด้วยขึ้นบรรทัดใหม่
+    pub fn render(&self, lock_info: &FileLockInfo) -> String {
+        let mut out = String::new();
+        if self.per_holder && !(lock_info.processes.is_empty() && self.when_free) {
+            for (i, process) in lock_info.processes.iter().enumerate() {
+                let mut line = String::new();
+                render_nodes(&self.nodes, lock_info, Some((i + 1, process)), &mut line);
+                push_line(&mut out, &line);
+            }
+        } else {
+            let mut text = String::new();
+            render_nodes(&self.nodes, lock_info, None, &mut text);
+            push_line(&mut out, &text);
+        }
+        out
+    }
+}
+
+fn push_line(out: &mut String, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    out.push_str(text);
+    if !text.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+fn render_nodes(nodes: &[Node], lock_info: &FileLockInfo, holder: Holder, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Field(field) => out.push_str(&value(*field, lock_info, holder)),
+            Node::EachHolder(body) => {
+                for (i, process) in lock_info.processes.iter().enumerate() {
+                    render_nodes(body, lock_info, Some((i + 1, process)), out);
+                }
+            }
+            Node::If {
+                field,
+                negate,
+                body,
+            } => {
+                if is_set(*field, lock_info, holder) != *negate {
+                    render_nodes(body, lock_info, holder, out);
+                }
+            }
+        }
+    }
+}
+
+fn value(field: Field, lock_info: &FileLockInfo, holder: Holder) -> String {
+    match field {
+        Field::File => return lock_info.file_path.clone(),
+        Field::Holders => return lock_info.processes.len().to_string(),
+        Field::Waiters => return lock_info.waiters.len().to_string(),
+        _ => {}
+    }
+
+    // field ของ process นอก {#holders} ถูกกันไว้ตอน parse แล้ว
+    let Some((index, process)) = holder else {
+        return String::new();
+    };
+    match field {
+        Field::Index => index.to_string(),
+        Field::Pid => process.pid.to_string(),
+        Field::Name => process.name.clone(),
+        Field::Path => process.path.clone(),
+        Field::User => process.user.clone().unwrap_or_default(),
+        Field::Mode => process
+            .strongest_lock_mode()
+            .map(|mode| mode.to_string())
+            .unwrap_or_default(),
+        Field::Locks => process
+            .locks
+            .iter()
+            .map(|lock| lock.to_string())
+            .collect::<Vec<_>>()
+            .join("; "),
+        Field::Opened => process.opened_paths().join("; "),
+        Field::File | Field::Holders | Field::Waiters => unreachable!(),
+    }
+}
+
+fn is_set(field: Field, lock_info: &FileLockInfo, holder: Holder) -> bool {
+    match field {
+        Field::Holders => !lock_info.processes.is_empty(),
+        Field::Waiters => !lock_info.waiters.is_empty(),
+        _ => !value(field, lock_info, holder).is_empty(),
+    }
+}
+
+fn has_each(nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| match node {
+        Node::EachHolder(_) => true,
+        Node::If { body, .. } => has_each(body),
+        Node::Text(_) | Node::Field(_) => false,
+    })
+}
+
+// มี {^holders}...{/holders} ที่ระดับใดก็ได้หรือไม่
+fn has_free_section(nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| match node {
+        Node::If {
+            field: Field::Holders,
+            negate: true,
+            ..
+        } => true,
+        Node::If { body, .. } | Node::EachHolder(body) => has_free_section(body),
+        Node::Text(_) | Node::Field(_) => false,
+    })
+}
+
+// มีการใช้ field ของ process นอก {#holders} หรือไม่
+fn outside_each(nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| match node {
+        Node::Field(field) => field.per_holder(),
+        Node::If { field, body, .. } => field.per_holder() || outside_each(body),
+        Node::EachHolder(_) | Node::Text(_) => false,
+    })
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    // อ่าน node จนจบ template หรือเจอ {/name} ที่ปิด section ปัจจุบัน
+    fn parse_nodes(&mut self, closing: Option<&str>) -> Result<Vec<Node>, String> {
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+
+        while let Some(c) = self.next() {
+            match c {
+                '\\' => match self.next() {
+                    Some('t') => text.push('\t'),
+                    Some('n') => text.push('\n'),
+                    Some('r') => text.push('\r'),
+                    Some('0') => text.push('\0'),
+                    Some('\\') => text.push('\\'),
+                    Some(other) => {
+                        text.push('\\');
+                        text.push(other);
+                    }
+                    None => text.push('\\'),
+                },
+                '{' if self.peek() == Some('{') => {
+                    self.pos += 1;
+                    text.push('{');
+                }
+                '}' if self.peek() == Some('}') => {
+                    self.pos += 1;
+                    text.push('}');
+                }
+                '}' => {
+                    return Err(
+                        "Unmatched '}' in template (use '}}' for a literal brace)".to_string()
+                    );
+                }
+                '{' => {
+                    let tag = self.read_tag()?;
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(std::mem::take(&mut text)));
+                    }
+
+                    if let Some(name) = tag.strip_prefix('/') {
+                        return match closing {
+                            Some(open) if open == name => Ok(nodes),
+                            Some(open) => Err(format!(
+                                "Template section {{{}}} is closed by {{/{}}}",
+                                open, name
+                            )),
+                            None => Err(format!("Unexpected {{/{}}} in template", name)),
+                        };
+                    }
+
+                    if let Some(name) = tag.strip_prefix('#') {
+                        if name != "holders" {
+                            return Err(format!(
+                                "Only {{#holders}} can be repeated, not {{#{}}}",
+                                name
+                            ));
+                        }
+                        let body = self.parse_nodes(Some(name))?;
+                        nodes.push(Node::EachHolder(body));
+                    } else if let Some((negate, name)) = tag
+                        .strip_prefix('?')
+                        .map(|name| (false, name))
+                        .or_else(|| tag.strip_prefix('^').map(|name| (true, name)))
+                    {
+                        let field = Field::parse(name)?;
+                        let body = self.parse_nodes(Some(name))?;
+                        nodes.push(Node::If {
+                            field,
+                            negate,
+                            body,
+                        });
+                    } else {
+                        nodes.push(Node::Field(Field::parse(&tag)?));
+                    }
+                }
+                _ => text.push(c),
+            }
+        }
+
+        if let Some(open) = closing {
+            return Err(format!("Template section {{{}}} is never closed", open));
+        }
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+        Ok(nodes)
+    }
+
+    // อ่านชื่อ tag หลัง '{' จนถึง '}'
+    fn read_tag(&mut self) -> Result<String, String> {
+        let mut tag = String::new();
+        loop {
+            match self.next() {
+                Some('}') => return Ok(tag.trim().to_string()),
+                Some('{') | None => {
+                    return Err(format!("Unterminated '{{{}' in template", tag));
+                }
+                Some(c) => tag.push(c),
+            }
+        }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.get(self.pos).copied();
+        self.pos += 1;
+        c
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock_info(pids: &[u32]) -> FileLockInfo {
+        FileLockInfo {
+            file_path: "data.db".to_string(),
+            processes: pids
+                .iter()
+                .map(|&pid| ProcessInfo {
+                    pid,
+                    name: format!("p{}", pid),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn render(template: &str, pids: &[u32]) -> String {
+        template
+            .parse::<Template>()
+            .unwrap()
+            .render(&lock_info(pids))
+    }
+
+    #[test]
+    fn renders_fields_sections_and_escapes() {
+        let template = "{file}:{^holders} free{/holders}{#holders} {name}({pid}){/holders}\\t{{x}}";
+        assert_eq!(render(template, &[]), "data.db: free\t{x}\n");
+        assert_eq!(render(template, &[1, 2]), "data.db: p1(1) p2(2)\t{x}\n");
+        assert_eq!(render("{?waiters}waiting{/waiters}", &[1]), "");
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        for template in [
+            "{nope}",
+            "{#waiters}x{/waiters}",
+            "{?holders}x",
+            "{?holders}x{/waiters}",
+            "x{/holders}",
+            "{file",
+            "a}b",
+            "{pid}{#holders}{name}{/holders}",
+        ] {
+            assert!(template.parse::<Template>().is_err(), "{}", template);
+        }
+    }
+
+    #[test]
+    fn per_holder_repeats_once_per_process() {
+        assert_eq!(render("{file}\t{pid}", &[1, 2]), "data.db\t1\ndata.db\t2\n");
+        assert_eq!(render("{file}\t{pid}", &[]), "");
+    }
+
+    #[test]
+    fn per_holder_renders_free_section_without_holders() {
+        let template = "{file} {name}{^holders}not locked{/holders}";
+        assert_eq!(render(template, &[]), "data.db not locked\n");
+        assert_eq!(render(template, &[7]), "data.db p7\n");
+    }
+}
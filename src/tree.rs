@@ -0,0 +1,73 @@
+// Copyright (c) 2025 Phumin Maliwan
+// SPDX-License-Identifier: MIT
+
+use file_lock_checker::FileLockInfo;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// node ของ tree: ชื่อ path ย่อยและ process ที่ใช้ path นั้น
+#[derive(Default)]
+struct Node {
+    children: BTreeMap<String, Node>,
+    holders: Vec<String>,
+}
+
+// แสดง path ที่ถูกใช้อยู่ใต้ root เป็น tree พร้อม process ที่ใช้แต่ละ path เช่น
+//
+//   /srv/build  [bash (812)]
+//   └── bin
+//       └── app.dll  [app (4242)]
+pub fn render_tree(root: &str, lock_infos: &[FileLockInfo]) -> String {
+    let mut tree = Node::default();
+    for lock_info in lock_infos {
+        if lock_info.processes.is_empty() {
+            continue;
+        }
+        let Ok(relative) = Path::new(&lock_info.file_path).strip_prefix(root) else {
+            continue;
+        };
+
+        let mut node = &mut tree;
+        for part in relative.iter() {
+            node = node
+                .children
+                .entry(part.to_string_lossy().into_owned())
+                .or_default();
+        }
+        node.holders.extend(
+            lock_info
+                .processes
+                .iter()
+                .map(|process| format!("{} ({})", process.name, process.pid)),
+        );
+    }
+
+    let mut out = String::new();
+    push_line(&mut out, root, &tree.holders);
+    render_children(&tree, "", &mut out);
+    out
+}
+
+fn render_children(node: &Node, prefix: &str, out: &mut String) {
+    let count = node.children.len();
+    for (i, (name, child)) in node.children.iter().enumerate() {
+        let last = i + 1 == count;
+        let branch = if last { "└── " } else { "├── " };
+        push_line(
+            out,
+            &format!("{}{}{}", prefix, branch, name),
+            &child.holders,
+        );
+
+        let indent = if last { "    " } else { "│   " };
+        render_children(child, &format!("{}{}", prefix, indent), out);
+    }
+}
+
+fn push_line(out: &mut String, label: &str, holders: &[String]) {
+    out.push_str(label);
+    if !holders.is_empty() {
+        out.push_str(&format!("  [{}]", holders.join(", ")));
+    }
+    out.push('\n');
+}
@@ -1,15 +1,286 @@
 // Copyright (c) 2025 Phumin Maliwan
 // SPDX-License-Identifier: MIT
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
     pub path: String,
+    // เจ้าของ process (None ถ้าไม่มีสิทธิ์อ่าน)
+    #[serde(default)]
+    pub user: Option<String>,
+    // lock ที่ process นี้ถือไว้จริง (ว่างถ้าแค่เปิดไฟล์ไว้)
+    pub locks: Vec<LockRecord>,
+    // ช่องทางที่ process ใช้ไฟล์ (ว่างถ้า backend ไม่รู้ เช่น Restart Manager)
+    #[serde(default)]
+    pub handles: Vec<OpenHandle>,
+}
+
+// ช่องทางที่ process อ้างถึงไฟล์
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandleKind {
+    // file descriptor ที่เปิดอยู่
+    Fd,
+    // current working directory
+    Cwd,
+    // root directory (chroot)
+    Root,
+    // executable ที่กำลังรัน
+    Exe,
+    // ไฟล์ที่ถูก mmap รวมถึง shared library
+    Mmap,
 }
 
-#[derive(Debug)]
+// การอ้างถึงไฟล์หนึ่งครั้งของ process
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenHandle {
+    pub kind: HandleKind,
+    // หมายเลข fd เมื่อ kind เป็น Fd
+    pub fd: Option<u32>,
+    // path ที่ process ใช้เปิดไฟล์ ตามที่ kernel รายงาน
+    pub path: String,
+    pub read: bool,
+    pub write: bool,
+    // lock ที่ตั้งผ่าน fd นี้ ตาม "lock:" ใน fdinfo (Linux) ส่วน handle แบบอื่นไม่มี
+    #[serde(default)]
+    pub locks: Vec<LockRecord>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FileLockInfo {
     pub file_path: String,
     pub processes: Vec<ProcessInfo>,
-}
\ No newline at end of file
+    // process ที่กำลังรอ lock บนไฟล์นี้
+    pub waiters: Vec<LockWait>,
+    // PID ที่ตรวจไม่ได้เพราะไม่มีสิทธิ์อ่าน ผลจึงอาจไม่ครบ
+    #[serde(default)]
+    pub inaccessible: Vec<u32>,
+}
+
+// ไฟล์ที่ถูกลบไปแล้วแต่ยังมี process เปิดค้างอยู่ จึงยังใช้พื้นที่ดิสก์จนกว่าจะปิด
+// (พื้นที่นี้ df นับแต่ du มองไม่เห็น)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedFile {
+    // path เดิมก่อนถูกลบ ไม่รวม " (deleted)" ที่ kernel ต่อท้าย
+    pub path: String,
+    pub dev: u64,
+    pub ino: u64,
+    // ขนาดของไฟล์ (byte)
+    pub size: u64,
+    // พื้นที่ที่ใช้จริงบนดิสก์ (byte) ไฟล์ sparse จะน้อยกว่า size
+    pub allocated: u64,
+    // filesystem ที่ไฟล์อยู่ (None ถ้าหา mount ไม่เจอ)
+    pub filesystem: Option<Filesystem>,
+    pub holders: Vec<DeletedFileHolder>,
+}
+
+// filesystem ตามที่ระบุใน /proc/self/mountinfo
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Filesystem {
+    pub mount_point: String,
+    // ชนิดของ filesystem เช่น ext4 หรือ xfs
+    pub fs_type: String,
+    // device หรือแหล่งที่ mount เช่น /dev/sda1
+    pub source: String,
+}
+
+// process ที่ยังเปิดไฟล์ที่ถูกลบไว้ พร้อม fd ทั้งหมดที่อ้างถึงไฟล์นั้น
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedFileHolder {
+    pub pid: u32,
+    pub name: String,
+    pub fds: Vec<u32>,
+}
+
+// ผลการหาไฟล์ที่ถูกลบแต่ยังเปิดอยู่ เรียงตามพื้นที่ที่ใช้จากมากไปน้อย
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedFiles {
+    pub files: Vec<DeletedFile>,
+    // PID ที่ตรวจไม่ได้เพราะไม่มีสิทธิ์อ่าน ผลจึงอาจไม่ครบ
+    pub inaccessible: Vec<u32>,
+}
+
+// process ที่กำลังรอ lock ซึ่งอีก process ถืออยู่
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockWait {
+    pub file_path: String,
+    pub waiter_pid: u32,
+    pub waiter_name: String,
+    pub requested: LockRecord,
+    pub holder_pid: u32,
+    pub holder_name: String,
+    pub held: LockRecord,
+}
+
+// ประเภทของ lock ตามที่ kernel รายงานใน /proc/locks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LockClass {
+    Posix,
+    Flock,
+    OfdLck,
+    Lease,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LockMode {
+    Read,
+    Write,
+}
+
+// ข้อมูล lock หนึ่งรายการ ช่วง byte เป็นแบบ inclusive และ end = None หมายถึงถึง EOF
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockRecord {
+    pub class: LockClass,
+    pub mode: LockMode,
+    pub mandatory: bool,
+    pub start: u64,
+    pub end: Option<u64>,
+}
+
+// คำขอ lock ที่ต้องการทดสอบว่าจะได้หรือไม่ โดย len = 0 หมายถึงถึง EOF
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LockRequest {
+    pub class: LockClass,
+    pub mode: LockMode,
+    pub start: u64,
+    pub len: u64,
+}
+
+// lock ที่ขัดแย้งกับคำขอ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockConflict {
+    // OFD lock ไม่มี PID
+    pub pid: Option<u32>,
+    pub name: Option<String>,
+    pub record: LockRecord,
+}
+
+impl ProcessInfo {
+    // mode ที่แรงที่สุดของ lock ที่ถืออยู่ (WRITE ชนะ READ) หรือ None ถ้าแค่เปิดไฟล์
+    pub fn strongest_lock_mode(&self) -> Option<LockMode> {
+        self.locks
+            .iter()
+            .map(|lock| lock.mode)
+            .max_by_key(|mode| *mode == LockMode::Write)
+    }
+
+    // path ที่ process ใช้เปิดไฟล์จริง (ผ่าน symlink, hardlink หรือหลัง rename) ไม่ซ้ำกัน ตามลำดับ handle
+    pub fn opened_paths(&self) -> Vec<&str> {
+        let mut paths: Vec<&str> = Vec::new();
+        for handle in &self.handles {
+            if !paths.contains(&handle.path.as_str()) {
+                paths.push(&handle.path);
+            }
+        }
+        paths
+    }
+}
+
+impl std::str::FromStr for LockClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "posix" => Ok(LockClass::Posix),
+            "flock" => Ok(LockClass::Flock),
+            "ofd" | "ofdlck" => Ok(LockClass::OfdLck),
+            "lease" => Ok(LockClass::Lease),
+            _ => Err(format!(
+                "Unknown lock flavour '{}' (expected posix, ofd or flock)",
+                s
+            )),
+        }
+    }
+}
+
+impl std::str::FromStr for LockMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "shared" | "read" => Ok(LockMode::Read),
+            "exclusive" | "write" => Ok(LockMode::Write),
+            _ => Err(format!(
+                "Unknown lock type '{}' (expected shared or exclusive)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LockClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LockClass::Posix => "POSIX",
+            LockClass::Flock => "FLOCK",
+            LockClass::OfdLck => "OFDLCK",
+            LockClass::Lease => "LEASE",
+        };
+        f.pad(name)
+    }
+}
+
+impl fmt::Display for LockMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LockMode::Read => "READ",
+            LockMode::Write => "WRITE",
+        };
+        f.pad(name)
+    }
+}
+
+impl fmt::Display for HandleKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HandleKind::Fd => "fd",
+            HandleKind::Cwd => "cwd",
+            HandleKind::Root => "root",
+            HandleKind::Exe => "exe",
+            HandleKind::Mmap => "mmap",
+        };
+        f.pad(name)
+    }
+}
+
+// แสดงเป็น เช่น "fd 3 (read/write) /tmp/data.db" หรือ "cwd /srv/app"
+impl fmt::Display for OpenHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(fd) = self.fd {
+            write!(f, " {}", fd)?;
+        }
+        match (self.read, self.write) {
+            (true, true) => write!(f, " (read/write)")?,
+            (false, true) => write!(f, " (write)")?,
+            (true, false) if self.kind == HandleKind::Fd => write!(f, " (read)")?,
+            _ => {}
+        }
+        write!(f, " {}", self.path)
+    }
+}
+
+impl fmt::Display for LockRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let enforcement = if self.mandatory {
+            "MANDATORY"
+        } else {
+            "ADVISORY"
+        };
+        let end = match self.end {
+            Some(end) => end.to_string(),
+            None => "EOF".to_string(),
+        };
+        write!(
+            f,
+            "{} {} {} bytes {}-{}",
+            self.class, enforcement, self.mode, self.start, end
+        )
+    }
+}
@@ -2,7 +2,6 @@
 // SPDX-License-Identifier: MIT
 
 use std::ffi::OsString;
-use std::fs::OpenOptions;
 use std::os::windows::ffi::OsStringExt;
 
 // แปลง wide string (UTF-16) เป็น Rust String
@@ -15,12 +14,3 @@ pub fn wide_string_to_string(wide_str: &[u16]) -> String {
         .to_string_lossy()
         .into_owned()
 }
-
-
-// ทดสอบการเข้าถึงไฟล์โดยตรง
-pub fn test_file_access(file_path: &str) -> Result<(), std::io::Error> {
-    // ลองเปิดไฟล์ในโหมด read-write เพื่อทดสอบว่า lock หรือไม่
-    OpenOptions::new().read(true).write(true).open(file_path)?;
-
-    Ok(())
-}
\ No newline at end of file